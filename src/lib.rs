@@ -0,0 +1,6 @@
+pub mod scrabbleutils;
+pub mod scrab_tui;
+pub mod scrab_bot;
+pub mod scrab_net;
+pub mod scrab_study;
+pub mod scrab_cli;
@@ -0,0 +1,254 @@
+use crate::scrabbleutils::{PlayerTrait, Action, Hand, Dico, Candidate, GameView,
+    generate_moves};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+/// Games between bots, to compare them
+mod tournament;
+
+pub use tournament::{Tournament, TournamentReport, GameResult, Entrant};
+
+/// Rough amount of tiles placed on the board between two turns of a bot
+const TILES_PER_ROUND : u32 = 8;
+
+/// How strong a `Bot` plays
+///
+/// The default one always plays its best move.
+pub struct BotConfig {
+    /// The bot picks randomly among its `top_k` best moves
+    pub top_k : usize,
+    /// Moves scoring more than this are never played
+    pub max_score : Option<u32>,
+    /// The bot paces its moves to end the game around this score
+    pub target_score : Option<u32>,
+    /// Only play words from this list, a list of common words for example
+    pub vocabulary : Option<Arc<Dico>>,
+}
+
+impl BotConfig {
+    /// Create the strongest configuration
+    pub fn new() -> BotConfig {
+        BotConfig {
+            top_k : 1,
+            max_score : None,
+            target_score : None,
+            vocabulary : None,
+        }
+    }
+
+    /// A bot for young or new players
+    pub fn beginner() -> BotConfig {
+        BotConfig {
+            top_k : 10,
+            max_score : Some(15),
+            ..BotConfig::new()
+        }
+    }
+
+    /// A bot for casual players
+    pub fn casual() -> BotConfig {
+        BotConfig {
+            top_k : 5,
+            max_score : Some(30),
+            ..BotConfig::new()
+        }
+    }
+}
+
+impl Default for BotConfig {
+    fn default() -> BotConfig {
+        BotConfig::new()
+    }
+}
+
+/// A computer player
+pub struct Bot {
+    name : String,
+    /// The dictionnary the game checks the moves against
+    dico : Arc<Dico>,
+    config : BotConfig,
+    /// Picks among the best moves
+    rng : StdRng,
+}
+
+impl Bot {
+    /// Create a bot
+    ///
+    /// # Arguments
+    /// * `name` - The name of the bot
+    /// * `dico` - The dictionnary used by the game
+    /// * `config` - How strong the bot plays
+    pub fn new(name : String, dico : Arc<Dico>, config : BotConfig) -> Bot {
+        Bot {
+            name,
+            dico,
+            config,
+            rng : StdRng::from_entropy(),
+        }
+    }
+
+    /// Make the bot pick its moves the same way for the same `seed`
+    pub fn set_seed(&mut self, seed : u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Get the score a move should make to reach the target score in time
+    fn pace(&self, game : &GameView, target : u32) -> u32 {
+        // The tiles still to be placed are in the bag or in the hands
+        let mut tiles_left = game.bag_len();
+        for player in 0..game.player_count() {
+            tiles_left += game.rack_size(player);
+        }
+        let turns_left = (tiles_left as u32 / TILES_PER_ROUND).max(1);
+        target.saturating_sub(game.score(game.me())) / turns_left
+    }
+
+    /// Sort the moves from the most wanted to the least wanted one
+    fn rank(&self, game : &GameView, moves : &mut [Candidate]) {
+        match self.config.target_score {
+            None => moves.sort_by_key(|c| Reverse(c.score())),
+            Some(target) => {
+                let pace = self.pace(game, target) as i64;
+                moves.sort_by_key(|c| (c.score() as i64 - pace).abs());
+            }
+        }
+    }
+}
+
+impl PlayerTrait for Bot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn play(&mut self, game : &GameView, hand : &Hand) -> Action {
+        let lexicon = match &self.config.vocabulary {
+            Some(vocabulary) => vocabulary,
+            None => &self.dico,
+        };
+        let mut moves = generate_moves(game.board(), hand, lexicon);
+        if self.config.vocabulary.is_some() {
+            moves.retain(|c| self.dico.exists(c.mv().word()));
+        }
+        if let Some(max) = self.config.max_score {
+            // The game takes a real tile before a blank for the same letter,
+            // so the move can score more than with the tiles it was found with
+            moves.retain(|c| matches!(game.preview(c.mv()), Ok(breakdown) if breakdown.total() <= max));
+        }
+        if moves.is_empty() {
+            return Action::Pass;
+        }
+
+        self.rank(game, &mut moves);
+        moves.truncate(self.config.top_k.max(1));
+        let chosen = self.rng.gen_range(0, moves.len());
+        Action::Place(moves.swap_remove(chosen).mv().clone())
+    }
+
+    fn move_score(&mut self, _score : u32) {
+    }
+
+    fn total_score(&mut self, _score : u32) {
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Bot, BotConfig};
+    use crate::scrabbleutils::{Action, Board, Dico, Game, Hand, PlayerTrait, Position, Tile, TileSet,
+        generate_moves};
+    use std::cmp::Reverse;
+    use std::sync::Arc;
+
+    /// Get a game where the first player holds `letters`, `?` being a blank
+    fn game(tileset : &TileSet, dico : &Arc<Dico>, letters : &str) -> (Game, Hand) {
+        let ab = tileset.alphabet();
+        let rack : Vec<Tile> = letters.chars().map(|c| match c {
+            '?' => Tile::new(0, 0, true),
+            _ => {
+                let letter = ab.letter(&c.to_string()).unwrap();
+                Tile::new(letter, tileset.get_points(letter), false)
+            }
+        }).collect();
+        let mut game = Game::new(tileset, Arc::clone(dico));
+        game.add_player(Box::new(Bot::new("bot".to_string(), Arc::clone(dico), BotConfig::new())));
+        let mut position = Position::new(Board::new());
+        position.set_rack(0, rack.clone());
+        game.set_position(&position, tileset);
+        (game, Hand::from_tiles(rack))
+    }
+
+    #[test]
+    fn levels() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let dico = Arc::new(Dico::from_words(vec!["AH", "HA", "EH", "HE", "TO", "AT", "TA", "ALE", "LET",
+            "THE", "TOE", "HAT", "EAT", "TEA", "HOLE", "HALT", "LATE", "TALE", "HELLO", "HOTEL", "LATHE"],
+            tileset.alphabet()));
+        let (game, hand) = game(&tileset, &dico, "AEHLLOT");
+        let view = game.view(0);
+        let mut scores : Vec<u32> = generate_moves(view.board(), &hand, &dico).iter().map(|c| c.score()).collect();
+        scores.sort_by_key(|score| Reverse(*score));
+
+        for seed in 0..20 {
+            let mut beginner = Bot::new("beginner".to_string(), Arc::clone(&dico), BotConfig::beginner());
+            beginner.set_seed(seed);
+            match beginner.play(&view, &hand) {
+                Action::Place(mv) => assert!(view.preview(&mv).unwrap().total() <= 15),
+                _ => panic!("the beginner didn't place a word"),
+            }
+
+            // Only the 3 best moves are picked
+            let mut bot = Bot::new("top".to_string(), Arc::clone(&dico), BotConfig { top_k : 3, ..BotConfig::new() });
+            bot.set_seed(seed);
+            match bot.play(&view, &hand) {
+                Action::Place(mv) => assert!(view.preview(&mv).unwrap().total() >= scores[2]),
+                _ => panic!("the bot didn't place a word"),
+            }
+        }
+    }
+
+    #[test]
+    fn vocabulary() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let dico = Arc::new(Dico::from_words(vec!["AT", "TA", "TAT"], ab));
+        let (game, hand) = game(&tileset, &dico, "AT");
+        let config = || BotConfig {
+            top_k : 10,
+            vocabulary : Some(Arc::new(Dico::from_words(vec!["AT"], ab))),
+            ..BotConfig::new()
+        };
+        for seed in 0..20 {
+            let mut bot = Bot::new("bot".to_string(), Arc::clone(&dico), config());
+            bot.set_seed(seed);
+            match bot.play(&game.view(0), &hand) {
+                Action::Place(mv) => assert_eq!(ab.decode(mv.word()), "AT"),
+                _ => panic!("the bot didn't place a word"),
+            }
+        }
+    }
+
+    #[test]
+    fn blank_under_cap() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let dico = Arc::new(Dico::from_words(vec!["AT", "TA"], tileset.alphabet()));
+        // With the blank, AT makes 2 points, but the game plays the real A
+        // and T for 4
+        let (game, hand) = game(&tileset, &dico, "AT?");
+        let view = game.view(0);
+        let capped = |max| BotConfig { top_k : 10, max_score : Some(max), ..BotConfig::new() };
+        for seed in 0..20 {
+            let mut bot = Bot::new("bot".to_string(), Arc::clone(&dico), capped(3));
+            bot.set_seed(seed);
+            assert!(matches!(bot.play(&view, &hand), Action::Pass));
+
+            let mut bot = Bot::new("bot".to_string(), Arc::clone(&dico), capped(4));
+            bot.set_seed(seed);
+            match bot.play(&view, &hand) {
+                Action::Place(mv) => assert_eq!(view.preview(&mv).unwrap().total(), 4),
+                _ => panic!("the bot didn't place a word"),
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::scrabbleutils::{Board, Dico, Game, PlayerTrait, TileSet};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// A game stops after this many moves refused in a row, from a player that
+/// would never play a valid one
+const REFUSED_MOVES : usize = 10;
+/// The number of standard deviations giving a 95% confidence interval
+const Z_95 : f64 = 1.96;
+
+/// Creates the player of a contestant for each game
+///
+/// It is given the dictionnary of the game and a seed, the same for the same
+/// game, to play the same way every time.
+pub type Entrant<'a> = &'a (dyn Fn(&Arc<Dico>, u64) -> Box<dyn PlayerTrait> + Sync);
+
+/// How a game between the two contestants went
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameResult {
+    /// The seed the bag was shuffled with
+    pub seed : u64,
+    /// The contestant who played first, 0 or 1
+    pub first : usize,
+    /// The final score of each contestant, in the order they were given
+    pub scores : [u32; 2],
+    /// The number of turns played, the refused moves excepted
+    pub turns : usize,
+    /// Whether the game ended without anyone going out
+    pub stalled : bool,
+}
+
+impl GameResult {
+    /// Get the score of the first contestant minus the one of the second
+    pub fn spread(&self) -> i64 {
+        self.scores[0] as i64 - self.scores[1] as i64
+    }
+}
+
+/// Plays many games between two contestants, without a terminal
+///
+/// Each seed is played twice, once with each contestant going first, so
+/// that the luck of the draw evens out. The games are shared among threads.
+pub struct Tournament<'a> {
+    tileset : &'a TileSet,
+    /// The dictionnary of every game, shared by the threads
+    dico : Arc<Dico>,
+    entrants : [Entrant<'a>; 2],
+    board : Board,
+    games : usize,
+    seed : u64,
+    threads : usize,
+}
+
+impl<'a> Tournament<'a> {
+    /// Create a tournament of 100 games, on as many threads as the computer
+    /// has cores
+    ///
+    /// # Arguments
+    /// * `tileset` - The tiles put in the bag.
+    /// * `dico` - The dictionnary of the games.
+    /// * `first` - The first contestant.
+    /// * `second` - The second contestant.
+    pub fn new(tileset : &'a TileSet, dico : Arc<Dico>, first : Entrant<'a>,
+            second : Entrant<'a>) -> Tournament<'a> {
+        Tournament {
+            tileset,
+            dico,
+            entrants : [first, second],
+            board : Board::new(),
+            games : 100,
+            seed : 0,
+            threads : thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    /// Play on another board, one with a different layout for example
+    pub fn set_board(&mut self, board : Board) {
+        self.board = board;
+    }
+
+    pub fn set_games(&mut self, games : usize) {
+        self.games = games;
+    }
+
+    /// Shuffle the bags from `seed`, the games are the same for the same seed
+    pub fn set_seed(&mut self, seed : u64) {
+        self.seed = seed;
+    }
+
+    /// Play on `threads` threads, at least one
+    pub fn set_threads(&mut self, threads : usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Play the game of index `game`
+    fn play_game(&self, game : usize) -> GameResult {
+        let seed = self.seed.wrapping_add(game as u64 / 2);
+        let first = game % 2;
+        let mut match_game = Game::new(self.tileset, Arc::clone(&self.dico));
+        match_game.set_board(self.board.clone());
+        match_game.set_seed(seed);
+        // The seats are in the order of play
+        match_game.set_first(0);
+        for seat in 0..2 {
+            let entrant = (first + seat) % 2;
+            let player_seed = seed.wrapping_mul(2).wrapping_add(entrant as u64 + 1);
+            match_game.add_player((self.entrants[entrant])(&self.dico, player_seed));
+        }
+        let mut turns = 0;
+        let mut refused = 0;
+        while !match_game.is_over() && refused < REFUSED_MOVES {
+            match match_game.play_turn() {
+                Ok(_) => {
+                    turns += 1;
+                    refused = 0;
+                }
+                Err(_) => refused += 1,
+            }
+        }
+        let stalled = match_game.out().is_none();
+        let mut scores = [0; 2];
+        for standing in match_game.end() {
+            scores[(first + standing.player) % 2] = standing.score;
+        }
+        GameResult { seed, first, scores, turns, stalled }
+    }
+
+    /// Play every game
+    ///
+    /// # Return Value
+    /// The results, in the order of the games whatever thread played them.
+    pub fn run(&self) -> TournamentReport {
+        let next = AtomicUsize::new(0);
+        let results : Mutex<Vec<(usize, GameResult)>> = Mutex::new(Vec::with_capacity(self.games));
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(self.games) {
+                scope.spawn(|| {
+                    loop {
+                        let game = next.fetch_add(1, Ordering::Relaxed);
+                        if game >= self.games {
+                            break;
+                        }
+                        let result = self.play_game(game);
+                        results.lock().unwrap().push((game, result));
+                    }
+                });
+            }
+        });
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(game, _)| *game);
+        TournamentReport { games : results.into_iter().map(|(_, result)| result).collect() }
+    }
+}
+
+/// The results of a tournament, from the point of view of the first
+/// contestant
+pub struct TournamentReport {
+    pub games : Vec<GameResult>,
+}
+
+impl TournamentReport {
+    /// Get how many games the first contestant won, drew and lost
+    pub fn record(&self) -> (usize, usize, usize) {
+        let won = self.games.iter().filter(|g| g.spread() > 0).count();
+        let drawn = self.games.iter().filter(|g| g.spread() == 0).count();
+        (won, drawn, self.games.len() - won - drawn)
+    }
+
+    /// Get the share of the games won by the first contestant, a draw
+    /// counting as half a win
+    pub fn win_rate(&self) -> f64 {
+        if self.games.is_empty() {
+            return 0.0;
+        }
+        let (won, drawn, _) = self.record();
+        (won as f64 + drawn as f64 / 2.0) / self.games.len() as f64
+    }
+
+    /// Get the 95% confidence interval of the win rate
+    ///
+    /// It is the Wilson score interval, which stays between 0 and 1 even
+    /// with few games or a lopsided result.
+    pub fn win_rate_interval(&self) -> (f64, f64) {
+        if self.games.is_empty() {
+            return (0.0, 1.0);
+        }
+        let n = self.games.len() as f64;
+        let p = self.win_rate();
+        let z2 = Z_95 * Z_95;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / (1.0 + z2 / n);
+        (center - margin, center + margin)
+    }
+
+    /// Get by how many points the first contestant wins on average
+    pub fn average_spread(&self) -> f64 {
+        if self.games.is_empty() {
+            return 0.0;
+        }
+        self.games.iter().map(|g| g.spread() as f64).sum::<f64>() / self.games.len() as f64
+    }
+
+    /// Get the 95% confidence interval of the average spread
+    pub fn spread_interval(&self) -> (f64, f64) {
+        let mean = self.average_spread();
+        let n = self.games.len();
+        if n < 2 {
+            return (mean, mean);
+        }
+        let variance = self.games.iter().map(|g| (g.spread() as f64 - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        let margin = Z_95 * (variance / n as f64).sqrt();
+        (mean - margin, mean + margin)
+    }
+
+    /// Get how many games ended without anyone going out
+    pub fn stalled(&self) -> usize {
+        self.games.iter().filter(|g| g.stalled).count()
+    }
+}
+
+impl fmt::Display for TournamentReport {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let (won, drawn, lost) = self.record();
+        let (low, high) = self.win_rate_interval();
+        let (spread_low, spread_high) = self.spread_interval();
+        writeln!(f, "{} games: {} won, {} drawn, {} lost by the first contestant", self.games.len(), won, drawn, lost)?;
+        writeln!(f, "win rate {:.1}% (95% between {:.1}% and {:.1}%)", self.win_rate() * 100.0, low * 100.0, high * 100.0)?;
+        write!(f, "average spread {:+.1} (95% between {:+.1} and {:+.1})", self.average_spread(), spread_low, spread_high)?;
+        if self.stalled() > 0 {
+            write!(f, "\n{} games ended without anyone going out", self.stalled())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Tournament, Entrant};
+    use crate::scrab_bot::{Bot, BotConfig};
+    use crate::scrabbleutils::{Dico, PlayerTrait, TileSet};
+    use std::sync::Arc;
+
+    #[test]
+    fn tournament() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let dico = Arc::new(Dico::from_file("dico.txt", tileset.alphabet()).unwrap());
+        let expert = |dico : &Arc<Dico>, seed : u64| -> Box<dyn PlayerTrait> {
+            let mut bot = Bot::new("expert".to_string(), Arc::clone(dico), BotConfig::new());
+            bot.set_seed(seed);
+            Box::new(bot)
+        };
+        let passer = |dico : &Arc<Dico>, _ : u64| -> Box<dyn PlayerTrait> {
+            let config = BotConfig { max_score : Some(0), ..BotConfig::new() };
+            Box::new(Bot::new("passer".to_string(), Arc::clone(dico), config))
+        };
+        let (expert, passer) : (Entrant, Entrant) = (&expert, &passer);
+
+        let mut tournament = Tournament::new(&tileset, dico, expert, passer);
+        tournament.set_games(6);
+        tournament.set_seed(42);
+        tournament.set_threads(3);
+        let report = tournament.run();
+        assert_eq!(report.games.len(), 6);
+        // Each seed is played by both sides
+        assert_eq!((report.games[0].seed, report.games[0].first), (42, 0));
+        assert_eq!((report.games[1].seed, report.games[1].first), (42, 1));
+        assert!(report.games.iter().all(|g| g.scores[1] == 0));
+        let (won, drawn, lost) = report.record();
+        assert_eq!(won + drawn + lost, 6);
+        assert_eq!(lost, 0);
+        let (low, high) = report.win_rate_interval();
+        assert!(low <= report.win_rate() && report.win_rate() <= high && high <= 1.0);
+        let (spread_low, spread_high) = report.spread_interval();
+        assert!(spread_low <= report.average_spread() && report.average_spread() <= spread_high);
+
+        // The same seed plays the same games, whatever the threads
+        tournament.set_threads(1);
+        assert_eq!(tournament.run().games, report.games);
+        assert!(report.to_string().starts_with("6 games: "));
+    }
+}
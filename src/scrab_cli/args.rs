@@ -0,0 +1,558 @@
+use std::fmt;
+use std::time::Duration;
+use crate::scrabbleutils::TimeControl;
+use crate::scrab_bot::BotConfig;
+
+/// The names given to the seats that are not named, in order
+const DEFAULT_NAMES : [&str; 4] = ["Yvan", "Ugo", "Lise", "Omar"];
+/// How many players a game can have
+const MIN_PLAYERS : usize = 2;
+const MAX_PLAYERS : usize = 4;
+
+pub const USAGE : &str = "\
+usage: scrabble-rs [play] [options]
+       scrabble-rs analyze --rack <letters> [--record <file.gcg>] [--turn <n>] [--top <n>] [--pre-endgame [--spread <n>]] [options]
+       scrabble-rs analyze --record <file.gcg> [--json] [options]
+       scrabble-rs replay <file.gcg> [options]
+       scrabble-rs study [--length <n>] [--stats <file>] [options]
+       scrabble-rs wordlist --length <n> [--alphagrams] [--output <file>] [options]
+       scrabble-rs tournament [--bot <level>[:<name>]] [--bot <level>[:<name>]] [--games <n>] [--threads <n>] [options]
+       scrabble-rs serve [--address <address>] [--remotes <n>] [--bots <n>] [options]
+       scrabble-rs connect <address> [name]
+
+options for every game:
+  --tileset <file>       the tiles of the game (english_tileset.txt)
+  --dico <file>          the words allowed (dico.txt)
+  --definitions <file>   the definitions of the words (definitions.txt if there is one)
+  --layout <file>        the bonuses of the board (the standard ones)
+  --seed <n>             shuffle the bag the same way every time
+  --rules <preset>       tournament (25 minutes), blitz (5 minutes and 5 seconds a move)
+                         or relaxed (an hour)
+
+options of play:
+  --players <n>          from 2 to 4 players (2)
+  --seat <kind>[:<name>] the next seat: human, beginner, casual or expert
+                         the seats not given are casual bots, after a human
+  --hotseat              the seats not given are humans
+  --fullscreen           draw the game on the whole terminal
+  --save <file.gcg>      save the game when it is over
+  --report               compare each move with the best one when the game is over
+  --edit                 set up the board, the racks and the bag before playing
+
+options of analyze:
+  --pre-endgame          judge the moves against every rack the opponent can hold, with 1 to 7 tiles in the bag
+  --spread <n>           by how many points the player is ahead, for --pre-endgame (0)
+
+options of study:
+  --length <n>           the number of letters of the racks (the rack size, for bingos)
+  --stats <file>         where the words found and missed are kept (study.txt)
+
+options of wordlist:
+  --length <n>           the number of letters of the words
+  --alphagrams           list the anagrams together, one line for each set of letters
+  --output <file>        write the list in a file rather than on the terminal
+
+options of tournament:
+  --bot <level>[:<name>] the next bot: beginner, casual or expert (expert, then casual)
+  --games <n>            how many games are played, half of them with each bot first (100)
+  --threads <n>          how many games are played at once (as many as the cores)";
+
+/// Why the command line can't be run
+#[derive(Debug)]
+pub enum CliError {
+    /// The arguments are wrong
+    Usage(String),
+    /// A file given could not be used
+    File {
+        path : String,
+        message : String,
+    },
+    /// The game could not be hosted or joined
+    Network(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}\n{}", message, USAGE),
+            CliError::File { path, message } => write!(f, "{}: {}", path, message),
+            CliError::Network(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// How long the players can think
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rules {
+    /// 25 minutes each, like in tournaments
+    Tournament,
+    /// 5 minutes each, and 5 seconds more after each move
+    Blitz,
+    /// An hour each
+    Relaxed,
+}
+
+impl Rules {
+    pub fn time_control(self) -> TimeControl {
+        match self {
+            Rules::Tournament => TimeControl::tournament(),
+            Rules::Blitz => TimeControl::new(Duration::from_secs(5 * 60), Duration::from_secs(5)),
+            Rules::Relaxed => TimeControl::new(Duration::from_secs(60 * 60), Duration::from_secs(0)),
+        }
+    }
+}
+
+/// How strong a bot plays
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Level {
+    Beginner,
+    Casual,
+    Expert,
+}
+
+impl Level {
+    pub fn config(self) -> BotConfig {
+        match self {
+            Level::Beginner => BotConfig::beginner(),
+            Level::Casual => BotConfig::casual(),
+            Level::Expert => BotConfig::new(),
+        }
+    }
+}
+
+/// Who sits at a seat of the game
+#[derive(Clone, Debug, PartialEq)]
+pub enum Seat {
+    Human(String),
+    Bot(String, Level),
+}
+
+impl Seat {
+    pub fn name(&self) -> &str {
+        match self {
+            Seat::Human(name) | Seat::Bot(name, _) => name,
+        }
+    }
+}
+
+/// What every game is made of
+#[derive(Clone, Debug, PartialEq)]
+pub struct Setup {
+    pub tileset : String,
+    pub dico : String,
+    /// None for the default file, which may be missing
+    pub definitions : Option<String>,
+    /// None for the standard board
+    pub layout : Option<String>,
+    pub seed : Option<u64>,
+    pub rules : Rules,
+}
+
+impl Default for Setup {
+    fn default() -> Setup {
+        Setup {
+            tileset : "english_tileset.txt".to_string(),
+            dico : "dico.txt".to_string(),
+            definitions : None,
+            layout : None,
+            seed : None,
+            rules : Rules::Tournament,
+        }
+    }
+}
+
+/// What the program was asked to do
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// Play a game in the terminal
+    Play {
+        setup : Setup,
+        seats : Vec<Seat>,
+        fullscreen : bool,
+        /// The GCG file the game is saved in
+        save : Option<String>,
+        /// Whether the moves are compared with the best ones at the end
+        report : bool,
+        /// Whether the position is set up by hand first
+        edit : bool,
+    },
+    /// List the best moves of a rack, or compare the moves of a game with
+    /// the best ones
+    Analyze {
+        setup : Setup,
+        /// None to go through the whole game
+        rack : Option<String>,
+        /// The game giving the board, an empty board if None
+        record : Option<String>,
+        /// How many turns of the game are on the board, all if None
+        turn : Option<usize>,
+        top : usize,
+        /// Whether the report of a game is written in JSON
+        json : bool,
+        /// Whether the moves are judged against the racks of the opponent
+        pre_endgame : bool,
+        /// By how many points the player is ahead
+        spread : i32,
+    },
+    /// Show a saved game turn by turn
+    Replay {
+        setup : Setup,
+        record : String,
+    },
+    /// Find the words of racks, the hard ones coming back
+    Study {
+        setup : Setup,
+        /// None for the size of a rack
+        length : Option<usize>,
+        stats : String,
+    },
+    /// List the words of a length, the most likely to be drawn first
+    WordList {
+        setup : Setup,
+        length : usize,
+        alphagrams : bool,
+        /// None to write on the terminal
+        output : Option<String>,
+    },
+    /// Play bots against each other, without a terminal
+    Tournament {
+        setup : Setup,
+        /// The name and level of the two bots
+        bots : Vec<(String, Level)>,
+        games : usize,
+        /// None for as many as the cores
+        threads : Option<usize>,
+    },
+    /// Host a game for players on other machines
+    Serve {
+        setup : Setup,
+        address : String,
+        remotes : usize,
+        bots : usize,
+    },
+    /// Play on a game server
+    Connect {
+        address : String,
+        name : String,
+    },
+    Help,
+}
+
+/// Reads the arguments one by one
+struct Arguments<'a> {
+    args : std::slice::Iter<'a, String>,
+}
+
+impl<'a> Arguments<'a> {
+    /// Get the value following `option`
+    fn value(&mut self, option : &str) -> Result<&'a str, CliError> {
+        match self.args.next() {
+            Some(value) => Ok(value),
+            None => Err(CliError::Usage(format!("{} needs a value", option))),
+        }
+    }
+
+    /// Get the number following `option`
+    fn number<T : std::str::FromStr>(&mut self, option : &str) -> Result<T, CliError> {
+        let value = self.value(option)?;
+        value.parse().map_err(|_|
+            CliError::Usage(format!("{} needs a number, not `{}`", option, value)))
+    }
+
+    /// Read `option` if it sets up the game
+    ///
+    /// # Return Value
+    /// Whether `option` was one of the options of every game.
+    fn setup(&mut self, option : &str, setup : &mut Setup) -> Result<bool, CliError> {
+        match option {
+            "--tileset" => setup.tileset = self.value(option)?.to_string(),
+            "--dico" => setup.dico = self.value(option)?.to_string(),
+            "--definitions" => setup.definitions = Some(self.value(option)?.to_string()),
+            "--layout" => setup.layout = Some(self.value(option)?.to_string()),
+            "--seed" => setup.seed = Some(self.number(option)?),
+            "--rules" => setup.rules = match self.value(option)? {
+                "tournament" => Rules::Tournament,
+                "blitz" => Rules::Blitz,
+                "relaxed" => Rules::Relaxed,
+                other => return Err(CliError::Usage(format!("unknown rules `{}`", other))),
+            },
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Read a seat like `expert:Ada`
+fn parse_seat(seat : &str, index : usize) -> Result<Seat, CliError> {
+    let (kind, name) = match seat.find(':') {
+        Some(colon) => (&seat[..colon], seat[colon + 1..].trim().to_string()),
+        None => (seat, String::new()),
+    };
+    let name = if name.is_empty() { DEFAULT_NAMES[index.min(MAX_PLAYERS - 1)].to_string() } else { name };
+    match kind {
+        "human" => Ok(Seat::Human(name)),
+        "beginner" => Ok(Seat::Bot(name, Level::Beginner)),
+        "casual" => Ok(Seat::Bot(name, Level::Casual)),
+        "expert" => Ok(Seat::Bot(name, Level::Expert)),
+        _ => Err(CliError::Usage(format!("unknown seat `{}`, it is human, beginner, casual or expert", kind))),
+    }
+}
+
+fn unknown(option : &str) -> CliError {
+    CliError::Usage(format!("unknown argument `{}`", option))
+}
+
+/// Read the command line, without the name of the program
+///
+/// Without a command, a game is played.
+///
+/// # Errors
+/// If an argument is unknown, misses its value or has a wrong one.
+pub fn parse_args(args : &[String]) -> Result<Command, CliError> {
+    let (command, rest) = match args.first() {
+        Some(first) if !first.starts_with('-') => (first.as_str(), &args[1..]),
+        _ => ("play", args),
+    };
+    let mut args = Arguments { args : rest.iter() };
+    let mut setup = Setup::default();
+    match command {
+        "play" => {
+            let mut players : Option<usize> = None;
+            let mut seats : Vec<Seat> = Vec::new();
+            let mut fullscreen = false;
+            let mut hotseat = false;
+            let mut save = None;
+            let mut report = false;
+            let mut edit = false;
+            while let Some(option) = args.args.next() {
+                match option.as_str() {
+                    "--players" => players = Some(args.number(option)?),
+                    "--seat" => seats.push(parse_seat(args.value(option)?, seats.len())?),
+                    "--fullscreen" => fullscreen = true,
+                    "--hotseat" => hotseat = true,
+                    "--save" => save = Some(args.value(option)?.to_string()),
+                    "--report" => report = true,
+                    "--edit" => edit = true,
+                    "--help" => return Ok(Command::Help),
+                    _ => if !args.setup(option, &mut setup)? {
+                        return Err(unknown(option));
+                    }
+                }
+            }
+            let players = players.unwrap_or_else(|| seats.len().max(MIN_PLAYERS));
+            if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players) {
+                return Err(CliError::Usage(format!("a game has from {} to {} players, not {}",
+                    MIN_PLAYERS, MAX_PLAYERS, players)));
+            }
+            if seats.len() > players {
+                return Err(CliError::Usage(format!("{} seats given for {} players",
+                    seats.len(), players)));
+            }
+            // Someone has to play in the terminal
+            while seats.len() < players {
+                let name = DEFAULT_NAMES[seats.len()].to_string();
+                let human = hotseat || seats.is_empty();
+                seats.push(if human { Seat::Human(name) } else { Seat::Bot(name, Level::Casual) });
+            }
+            Ok(Command::Play { setup, seats, fullscreen, save, report, edit })
+        }
+        "analyze" => {
+            let mut rack = None;
+            let mut record = None;
+            let mut turn = None;
+            let mut top = 10;
+            let mut json = false;
+            let mut pre_endgame = false;
+            let mut spread = 0;
+            while let Some(option) = args.args.next() {
+                match option.as_str() {
+                    "--rack" => rack = Some(args.value(option)?.to_string()),
+                    "--record" => record = Some(args.value(option)?.to_string()),
+                    "--turn" => turn = Some(args.number(option)?),
+                    "--top" => top = args.number(option)?,
+                    "--json" => json = true,
+                    "--pre-endgame" => pre_endgame = true,
+                    "--spread" => spread = args.number(option)?,
+                    _ => if !args.setup(option, &mut setup)? {
+                        return Err(unknown(option));
+                    }
+                }
+            }
+            if rack.is_none() && record.is_none() {
+                return Err(CliError::Usage("analyze needs a --rack or a --record".to_string()));
+            }
+            if pre_endgame && rack.is_none() {
+                return Err(CliError::Usage("--pre-endgame needs a --rack".to_string()));
+            }
+            Ok(Command::Analyze { setup, rack, record, turn, top, json, pre_endgame, spread })
+        }
+        "replay" => {
+            let mut record = None;
+            while let Some(option) = args.args.next() {
+                if args.setup(option, &mut setup)? {
+                    continue;
+                }
+                if option.starts_with('-') || record.is_some() {
+                    return Err(unknown(option));
+                }
+                record = Some(option.to_string());
+            }
+            let record = record.ok_or_else(|| CliError::Usage("replay needs a file".to_string()))?;
+            Ok(Command::Replay { setup, record })
+        }
+        "study" => {
+            let mut length = None;
+            let mut stats = "study.txt".to_string();
+            while let Some(option) = args.args.next() {
+                match option.as_str() {
+                    "--length" => length = Some(args.number(option)?),
+                    "--stats" => stats = args.value(option)?.to_string(),
+                    _ => if !args.setup(option, &mut setup)? {
+                        return Err(unknown(option));
+                    }
+                }
+            }
+            if length == Some(0) {
+                return Err(CliError::Usage("a rack has at least one letter".to_string()));
+            }
+            Ok(Command::Study { setup, length, stats })
+        }
+        "wordlist" => {
+            let mut length = None;
+            let mut alphagrams = false;
+            let mut output = None;
+            while let Some(option) = args.args.next() {
+                match option.as_str() {
+                    "--length" => length = Some(args.number(option)?),
+                    "--alphagrams" => alphagrams = true,
+                    "--output" => output = Some(args.value(option)?.to_string()),
+                    _ => if !args.setup(option, &mut setup)? {
+                        return Err(unknown(option));
+                    }
+                }
+            }
+            match length {
+                Some(0) => Err(CliError::Usage("a word has at least one letter".to_string())),
+                Some(length) => Ok(Command::WordList { setup, length, alphagrams, output }),
+                None => Err(CliError::Usage("wordlist needs a --length".to_string())),
+            }
+        }
+        "tournament" => {
+            let mut bots : Vec<(String, Level)> = Vec::new();
+            let mut games = 100;
+            let mut threads = None;
+            while let Some(option) = args.args.next() {
+                match option.as_str() {
+                    "--bot" => match parse_seat(args.value(option)?, bots.len())? {
+                        Seat::Bot(name, level) if bots.len() < 2 => bots.push((name, level)),
+                        Seat::Bot(..) => return Err(CliError::Usage("a tournament is between two bots".to_string())),
+                        Seat::Human(_) => return Err(CliError::Usage("only bots play in a tournament".to_string())),
+                    },
+                    "--games" => games = args.number(option)?,
+                    "--threads" => threads = Some(args.number(option)?),
+                    _ => if !args.setup(option, &mut setup)? {
+                        return Err(unknown(option));
+                    }
+                }
+            }
+            for level in [Level::Expert, Level::Casual].iter().skip(bots.len()) {
+                bots.push((DEFAULT_NAMES[bots.len()].to_string(), *level));
+            }
+            if games == 0 || threads == Some(0) {
+                return Err(CliError::Usage("a tournament needs at least one game and one thread".to_string()));
+            }
+            Ok(Command::Tournament { setup, bots, games, threads })
+        }
+        "serve" => {
+            let mut address = "0.0.0.0:7878".to_string();
+            let mut remotes = 2;
+            let mut bots = 0;
+            while let Some(option) = args.args.next() {
+                match option.as_str() {
+                    "--address" => address = args.value(option)?.to_string(),
+                    "--remotes" => remotes = args.number(option)?,
+                    "--bots" => bots = args.number(option)?,
+                    _ => if !args.setup(option, &mut setup)? {
+                        return Err(unknown(option));
+                    }
+                }
+            }
+            let players = remotes + bots;
+            if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&players) {
+                return Err(CliError::Usage(format!("a game has from {} to {} players, not {}",
+                    MIN_PLAYERS, MAX_PLAYERS, players)));
+            }
+            Ok(Command::Serve { setup, address, remotes, bots })
+        }
+        "connect" => match rest {
+            [address] => Ok(Command::Connect { address : address.clone(), name : DEFAULT_NAMES[0].to_string() }),
+            [address, name] => Ok(Command::Connect { address : address.clone(), name : name.clone() }),
+            _ => Err(CliError::Usage("connect needs an address and maybe a name".to_string())),
+        },
+        "help" => Ok(Command::Help),
+        _ => Err(CliError::Usage(format!("unknown command `{}`", command))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_args, Command, Seat, Level, Rules, Setup, CliError};
+
+    fn parse(line : &str) -> Result<Command, CliError> {
+        let args : Vec<String> = line.split_whitespace().map(|a| a.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn arguments() {
+        let default = Command::Play {
+            setup : Setup::default(),
+            seats : vec![Seat::Human("Yvan".to_string()), Seat::Bot("Ugo".to_string(), Level::Casual)],
+            fullscreen : false,
+            save : None,
+            report : false,
+            edit : false,
+        };
+        assert_eq!(parse("").unwrap(), default);
+        assert_eq!(parse("play").unwrap(), default);
+
+        match parse("--players 3 --seat expert:Ada --seat human --hotseat --seed 4 --rules blitz").unwrap() {
+            Command::Play { setup, seats, .. } => {
+                assert_eq!(seats, vec![Seat::Bot("Ada".to_string(), Level::Expert),
+                    Seat::Human("Ugo".to_string()), Seat::Human("Lise".to_string())]);
+                assert_eq!(setup.seed, Some(4));
+                assert_eq!(setup.rules, Rules::Blitz);
+            }
+            _ => panic!("not a game"),
+        }
+
+        match parse("analyze --rack AB?CD --record game.gcg --turn 3 --dico words.txt").unwrap() {
+            Command::Analyze { setup, rack, record, turn, top, json, pre_endgame, .. } => {
+                assert_eq!((rack.as_deref(), record.as_deref(), turn, top), (Some("AB?CD"), Some("game.gcg"), Some(3), 10));
+                assert!(!json && !pre_endgame);
+                assert_eq!(setup.dico, "words.txt");
+            }
+            _ => panic!("not an analysis"),
+        }
+        assert!(matches!(parse("analyze --record game.gcg --json").unwrap(),
+            Command::Analyze { rack : None, json : true, .. }));
+        assert!(matches!(parse("analyze --rack AB --pre-endgame --spread -12").unwrap(),
+            Command::Analyze { pre_endgame : true, spread : -12, .. }));
+        assert!(parse("analyze --record game.gcg --pre-endgame").is_err());
+        assert!(matches!(parse("replay game.gcg").unwrap(), Command::Replay { record, .. } if record == "game.gcg"));
+        assert!(matches!(parse("study --length 5").unwrap(),
+            Command::Study { length : Some(5), ref stats, .. } if stats == "study.txt"));
+        assert!(matches!(parse("tournament --bot beginner --games 10").unwrap(),
+            Command::Tournament { ref bots, games : 10, threads : None, .. }
+                if bots == &[("Yvan".to_string(), Level::Beginner), ("Ugo".to_string(), Level::Casual)]));
+        assert!(matches!(parse("wordlist --length 7 --alphagrams").unwrap(),
+            Command::WordList { length : 7, alphagrams : true, output : None, .. }));
+        assert!(matches!(parse("serve --bots 1").unwrap(), Command::Serve { remotes : 2, bots : 1, .. }));
+
+        for wrong in ["--players 5", "--players 2 --seat human --seat human --seat human",
+                "--seat wizard", "--seed x", "--rules fast", "--dico", "analyze", "replay",
+                "serve --remotes 1", "study --length 0", "wordlist", "tournament --bot human", "tournament --games 0", "connect", "fly"].iter() {
+            assert!(matches!(parse(wrong), Err(CliError::Usage(_))), "{}", wrong);
+        }
+    }
+}
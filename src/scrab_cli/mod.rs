@@ -0,0 +1,400 @@
+use crate::scrabbleutils::{Dico, TileSet, Game, Turn, MoveError, Letter, Board, Hand, Tile, PlayerTrait,
+    GameRecord, GameRecorder, GameAnalysis, Play, Position, PreEndgame, WILDCARD, best_moves};
+use crate::{scrab_tui, scrab_bot, scrab_net, scrab_study};
+use std::io::{self, BufRead};
+use std::net::{TcpListener, TcpStream};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Reading the command line
+mod args;
+
+pub use args::{parse_args, Command, Setup, Seat, Level, Rules, CliError, USAGE};
+
+/// Stands for a wildcard in a rack, as well as the tileset's one
+const RACK_BLANK : char = '?';
+
+/// What a game is played with, loaded from the files of a `Setup`
+struct Material {
+    tileset : TileSet,
+    dico : Arc<Dico>,
+    board : Board,
+}
+
+/// Load the tileset, the dictionnary and the board of `setup`
+///
+/// # Errors
+/// If a file can't be read or is malformed. The default definitions may
+/// be missing.
+fn load(setup : &Setup) -> Result<Material, CliError> {
+    // The errors of the dictionnary already tell the file
+    let file_error = |path : &str, message : String| CliError::File {
+        path : path.to_string(),
+        message : message.strip_prefix(&format!("{}: ", path)).map_or(message.clone(), str::to_string),
+    };
+    let tileset = TileSet::from_file(&setup.tileset)
+        .map_err(|e| file_error(&setup.tileset, e.to_string()))?;
+    let mut dico = Dico::from_file(&setup.dico, tileset.alphabet())
+        .map_err(|e| file_error(&setup.dico, e.to_string()))?;
+    let definitions = setup.definitions.as_deref().unwrap_or("definitions.txt");
+    if let Err(e) = dico.load_definitions(definitions) {
+        if setup.definitions.is_some() || e.kind() != io::ErrorKind::NotFound {
+            return Err(file_error(definitions, e.to_string()));
+        }
+    }
+    let board = match &setup.layout {
+        Some(layout) => Board::from_layout_file(layout).map_err(|e| file_error(layout, e.to_string()))?,
+        None => Board::new(),
+    };
+    Ok(Material {
+        tileset,
+        dico : Arc::new(dico),
+        board,
+    })
+}
+
+/// Create a game without players, set up as asked
+fn new_game(material : &Material, setup : &Setup) -> Game {
+    let mut game = Game::new(&material.tileset, Arc::clone(&material.dico));
+    game.set_board(material.board.clone());
+    game.set_time_control(setup.rules.time_control());
+    if let Some(seed) = setup.seed {
+        game.set_seed(seed);
+    }
+    game
+}
+
+/// Create a bot, seeded after the game so it plays the same way too
+fn new_bot(name : &str, level : Level, dico : &Arc<Dico>, setup : &Setup, seat : usize) -> scrab_bot::Bot {
+    let mut bot = scrab_bot::Bot::new(name.to_string(), Arc::clone(dico), level.config());
+    if let Some(seed) = setup.seed {
+        bot.set_seed(seed.wrapping_add(seat as u64 + 1));
+    }
+    bot
+}
+
+/// Get what creates a bot for each game of a tournament
+fn entrant(name : &str, level : Level) -> impl Fn(&Arc<Dico>, u64) -> Box<dyn PlayerTrait> + Sync + '_ {
+    move |dico, seed| {
+        let mut bot = scrab_bot::Bot::new(name.to_string(), Arc::clone(dico), level.config());
+        bot.set_seed(seed);
+        Box::new(bot)
+    }
+}
+
+/// Read a rack like `AEH?LLO`, `?` or the wildcard of the tileset being a blank
+fn parse_rack(rack : &str, tileset : &TileSet) -> Option<Hand> {
+    let blank = |c : char| c == RACK_BLANK || c == WILDCARD;
+    let letters : String = rack.chars().filter(|c| !blank(*c)).collect();
+    let mut tiles : Vec<Tile> = tileset.alphabet().encode(&letters)?.into_iter()
+        .map(|letter| Tile::new(letter, tileset.get_points(letter), false))
+        .collect();
+    tiles.extend(rack.chars().filter(|c| blank(*c)).map(|_| Tile::new(0, 0, true)));
+    if tiles.is_empty() || tiles.len() > tileset.rack_size() {
+        return None;
+    }
+    Some(Hand::from_tiles(tiles))
+}
+
+/// Play a game in the terminal
+fn play(setup : &Setup, seats : &[Seat], fullscreen : bool, save : Option<&str>, report : bool, edit : bool)
+        -> Result<(), CliError> {
+    let material = load(setup)?;
+    let alphabet = material.tileset.alphabet();
+    let mut game = new_game(&material, setup);
+    // The hands are hidden from the other humans sitting at the terminal
+    let hotseat = seats.iter().filter(|seat| matches!(seat, Seat::Human(_))).count() > 1;
+    for (index, seat) in seats.iter().enumerate() {
+        let player : Box<dyn PlayerTrait> = match seat {
+            Seat::Human(name) => {
+                let player : Box<dyn PlayerTrait> = if fullscreen {
+                    Box::new(scrab_tui::FullScreenPlayer::new(name.clone(), alphabet.clone()))
+                }
+                else {
+                    Box::new(scrab_tui::SimplePlayer::new(name.clone(), alphabet.clone()))
+                };
+                if hotseat { Box::new(scrab_tui::Hotseat::new(player, scrab_tui::StdinLines::new())) } else { player }
+            }
+            Seat::Bot(name, level) => Box::new(new_bot(name, *level, &material.dico, setup, index)),
+        };
+        game.add_player(player);
+    }
+    if edit {
+        let position = Position::new(material.board.clone());
+        let position = match scrab_tui::edit_position(&mut io::stdin().lock(), position, &material.tileset,
+                &material.dico, seats.len()) {
+            Some(position) => position,
+            // Nothing more can be read
+            None => return Ok(()),
+        };
+        game.set_position(&position, &material.tileset);
+        if let Some(seed) = setup.seed {
+            game.set_seed(seed);
+        }
+    }
+    let recorder = Rc::new(GameRecorder::new());
+    game.add_observer(recorder.clone());
+    // The whole screen is redrawn on each turn, the commentator would be lost
+    if !fullscreen {
+        game.add_observer(Rc::new(scrab_tui::Commentator::new(alphabet.clone())));
+    }
+
+    // The first player is drawn, unless the position was set up
+    game.start();
+    if !fullscreen {
+        println!("{} plays first", game.players()[game.current()].name);
+    }
+    while !game.is_over() {
+        if !fullscreen {
+            scrab_tui::print_clocks(&game);
+        }
+        match game.play_turn() {
+            Ok(Turn::Placed(breakdown)) if !fullscreen => {
+                let words : Vec<Vec<Letter>> = breakdown.words.into_iter().map(|w| w.0).collect();
+                scrab_tui::print_definitions(&material.dico, &words);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                scrab_tui::handle_error(e.to_string().as_str());
+                if let MoveError::UnknownWord(word) = &e {
+                    if !fullscreen {
+                        scrab_tui::print_challenged(&material.dico, word);
+                    }
+                }
+            }
+        }
+    }
+
+    let standings = game.end();
+    if fullscreen {
+        for (rank, standing) in standings.iter().enumerate() {
+            println!("{}. {} : {} points", rank + 1, standing.name, standing.score);
+        }
+    }
+    println!("party ended in {} turns", game.round() - 1);
+    if let Some(path) = save {
+        recorder.record().save(path, alphabet)
+            .map_err(|e| CliError::File { path : path.to_string(), message : e.to_string() })?;
+        println!("game saved in {}", path);
+    }
+    if report {
+        let analysis = GameAnalysis::new(&recorder.record(), material.board, &material.tileset, &material.dico);
+        print!("{}", analysis.to_text(alphabet));
+    }
+    Ok(())
+}
+
+/// Read the GCG file `path`
+fn read_record(path : &str, tileset : &TileSet) -> Result<GameRecord, CliError> {
+    GameRecord::from_gcg_file(path, tileset)
+        .map_err(|e| CliError::File { path : path.to_string(), message : e.to_string() })
+}
+
+/// Compare each move of a saved game with the best one
+fn report(setup : &Setup, path : &str, json : bool) -> Result<(), CliError> {
+    let material = load(setup)?;
+    let alphabet = material.tileset.alphabet();
+    let record = read_record(path, &material.tileset)?;
+    let analysis = GameAnalysis::new(&record, material.board, &material.tileset, &material.dico);
+    if json {
+        println!("{}", analysis.to_json(alphabet));
+    }
+    else {
+        print!("{}", analysis.to_text(alphabet));
+    }
+    Ok(())
+}
+
+/// List the best moves of `rack`, on the board of a saved game
+///
+/// With a `pre_endgame` spread, the moves are judged against every rack the
+/// opponent can hold.
+fn analyze(setup : &Setup, rack : &str, record : Option<&str>, turn : Option<usize>, top : usize,
+        pre_endgame : Option<i32>) -> Result<(), CliError> {
+    let material = load(setup)?;
+    let tileset = &material.tileset;
+    let hand = parse_rack(rack, tileset).ok_or_else(|| CliError::Usage(format!(
+        "`{}` is not a rack: from 1 to {} letters of the game, `?` for a blank", rack, tileset.rack_size())))?;
+    let mut board = material.board.clone();
+    if let Some(path) = record {
+        let record = read_record(path, tileset)?;
+        let turns = turn.unwrap_or(record.turns.len());
+        if turns > record.turns.len() {
+            return Err(CliError::Usage(format!("{} has only {} turns", path, record.turns.len())));
+        }
+        record.replay(&mut board, turns, tileset);
+    }
+    scrab_tui::print_board(&board, tileset.alphabet());
+    if let Some(spread) = pre_endgame {
+        let analysis = PreEndgame::new(&board, &hand.get(), tileset, &material.dico, spread, top)
+            .ok_or_else(|| CliError::Usage(format!("the bag must hold from 1 to {} tiles", hand.get().len())))?;
+        print!("{}", analysis.to_text(tileset.alphabet()));
+        return Ok(());
+    }
+    let candidates = best_moves(&board, &hand, &material.dico, top);
+    if candidates.is_empty() {
+        println!("No move can be made");
+    }
+    for (rank, candidate) in candidates.iter().enumerate() {
+        println!("{}. {} : {} points", rank + 1, candidate.mv().notation(tileset.alphabet()),
+            candidate.score());
+    }
+    Ok(())
+}
+
+/// Show a saved game turn by turn, Enter going to the next one
+fn replay<R : BufRead>(setup : &Setup, path : &str, input : &mut R) -> Result<(), CliError> {
+    let material = load(setup)?;
+    let tileset = &material.tileset;
+    let alphabet = tileset.alphabet();
+    let record = read_record(path, tileset)?;
+    let mut board = material.board.clone();
+    // Nothing more to read goes through the game without stopping
+    let mut step = true;
+    for turn in record.turns.iter() {
+        let name = &record.players[turn.player];
+        match &turn.play {
+            Play::Placed(mv) => {
+                let tiles = turn.tiles(&board, tileset);
+                board.add_move(mv.clone(), tiles);
+                scrab_tui::print_board(&board, alphabet);
+                println!("{} played {} for {} points, {} in total", name, mv.notation(alphabet),
+                    turn.score, turn.total);
+            }
+            Play::Exchanged(count) => println!("{} exchanged {} tiles", name, count),
+            Play::Passed => println!("{} passed", name),
+            Play::Leftover(_) if turn.score > 0 =>
+                println!("{} went out and got {} points, {} in total", name, turn.score, turn.total),
+            Play::Leftover(tiles) => println!("{} lost {} points for {} tiles left, {} in total", name,
+                -turn.score, tiles.len(), turn.total),
+            Play::Adjustment(reason) =>
+                println!("{} got {} points for {}, {} in total", name, turn.score, reason, turn.total),
+        }
+        if step {
+            let mut line = String::new();
+            step = matches!(input.read_line(&mut line), Ok(read) if read > 0);
+        }
+    }
+    Ok(())
+}
+
+/// Ask for the words of racks, keeping what is found in `stats`
+fn study(setup : &Setup, length : Option<usize>, stats : &str) -> Result<(), CliError> {
+    let material = load(setup)?;
+    let record = scrab_study::StudyRecord::from_file(stats)
+        .map_err(|e| CliError::File { path : stats.to_string(), message : e.to_string() })?;
+    let length = length.unwrap_or(material.tileset.rack_size());
+    let mut quiz = scrab_study::Quiz::new(&material.tileset, &material.dico, record, length);
+    if let Some(seed) = setup.seed {
+        quiz.set_seed(seed);
+    }
+    let today = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() / (24 * 60 * 60));
+    scrab_tui::run_quiz(&mut io::stdin().lock(), &mut quiz, today);
+    quiz.record().save(stats).map_err(|e| CliError::File { path : stats.to_string(), message : e.to_string() })
+}
+
+/// List the words of `length` letters, the most likely to be drawn first
+fn word_list(setup : &Setup, length : usize, alphagrams : bool, output : Option<&str>) -> Result<(), CliError> {
+    let material = load(setup)?;
+    let list = scrab_study::WordList::new(&material.dico, &material.tileset, length);
+    match output {
+        Some(path) => list.save(path, alphagrams)
+            .map_err(|e| CliError::File { path : path.to_string(), message : e.to_string() }),
+        None => {
+            print!("{}", if alphagrams { list.groups_to_text() } else { list.to_text() });
+            Ok(())
+        }
+    }
+}
+
+/// Play two bots against each other and tell which one is stronger
+fn tournament(setup : &Setup, bots : &[(String, Level)], games : usize, threads : Option<usize>)
+        -> Result<(), CliError> {
+    let material = load(setup)?;
+    let (first, second) = (entrant(&bots[0].0, bots[0].1), entrant(&bots[1].0, bots[1].1));
+    let mut tournament = scrab_bot::Tournament::new(&material.tileset, Arc::clone(&material.dico),
+        &first, &second);
+    tournament.set_board(material.board.clone());
+    tournament.set_games(games);
+    tournament.set_seed(setup.seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())));
+    if let Some(threads) = threads {
+        tournament.set_threads(threads);
+    }
+    println!("{} against {}, {} games", bots[0].0, bots[1].0, games);
+    println!("{}", tournament.run());
+    Ok(())
+}
+
+/// Host a game for players on other machines
+fn serve(setup : &Setup, address : &str, remotes : usize, bots : usize) -> Result<(), CliError> {
+    let material = load(setup)?;
+    let alphabet = material.tileset.alphabet();
+    let listener = TcpListener::bind(address)
+        .map_err(|e| CliError::Network(format!("could not listen on {}: {}", address, e)))?;
+    println!("Waiting for {} players on {}", remotes, address);
+    let mut game = new_game(&material, setup);
+    let mut connections = Vec::with_capacity(remotes);
+    while connections.len() < remotes {
+        let (stream, peer) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                scrab_tui::handle_error(&format!("Could not accept a player: {}", e));
+                continue;
+            }
+        };
+        match scrab_net::RemotePlayer::accept(stream, alphabet.clone()) {
+            Ok(remote) => {
+                println!("{} joined from {}", remote.name(), peer);
+                connections.push(remote.connection());
+                game.add_player(Box::new(remote));
+            }
+            Err(e) => scrab_tui::handle_error(&format!("{} could not join: {}", peer, e)),
+        }
+    }
+    for i in 0..bots {
+        let bot = new_bot(&format!("Bot_{}", i + 1), Level::Casual, &material.dico, setup, remotes + i);
+        game.add_player(Box::new(bot));
+    }
+    game.add_observer(Rc::new(scrab_net::Broadcaster::new(connections.clone(), alphabet.clone())));
+
+    for (rank, standing) in scrab_net::host(&mut game, &connections).iter().enumerate() {
+        println!("{}. {} : {} points", rank + 1, standing.name, standing.score);
+    }
+    Ok(())
+}
+
+/// Play on a game server
+fn connect(address : &str, name : &str) -> Result<(), CliError> {
+    TcpStream::connect(address)
+        .and_then(|stream| scrab_tui::run_client(stream, name, io::stdin().lock()))
+        .map_err(|e| CliError::Network(format!("lost the server {}: {}", address, e)))
+}
+
+/// Do what the command line asks
+///
+/// # Errors
+/// If the configuration is wrong, like a missing file.
+pub fn run(command : &Command) -> Result<(), CliError> {
+    match command {
+        Command::Play { setup, seats, fullscreen, save, report : analysis, edit } =>
+            play(setup, seats, *fullscreen, save.as_deref(), *analysis, *edit),
+        Command::Analyze { setup, rack : Some(rack), record, turn, top, pre_endgame, spread, .. } =>
+            analyze(setup, rack, record.as_deref(), *turn, *top, pre_endgame.then_some(*spread)),
+        Command::Analyze { setup, rack : None, record : Some(record), json, .. } => report(setup, record, *json),
+        Command::Analyze { rack : None, record : None, .. } =>
+            Err(CliError::Usage("analyze needs a --rack or a --record".to_string())),
+        Command::Replay { setup, record } => replay(setup, record, &mut io::stdin().lock()),
+        Command::Study { setup, length, stats } => study(setup, *length, stats),
+        Command::Tournament { setup, bots, games, threads } => tournament(setup, bots, *games, *threads),
+        Command::WordList { setup, length, alphagrams, output } =>
+            word_list(setup, *length, *alphagrams, output.as_deref()),
+        Command::Serve { setup, address, remotes, bots } => serve(setup, address, *remotes, *bots),
+        Command::Connect { address, name } => connect(address, name),
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
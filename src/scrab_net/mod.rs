@@ -0,0 +1,7 @@
+/// The lines the server and its clients send each other
+mod protocol;
+/// Hosts a game for players on other machines
+mod server;
+
+pub use protocol::{ServerMessage, ClientMessage, sanitize_name};
+pub use server::{Connection, RemotePlayer, Broadcaster, host};
@@ -0,0 +1,273 @@
+use crate::scrabbleutils::{Tile, Standing, Letter};
+use std::fmt;
+
+/// Stands for an empty spot of the board
+const EMPTY : &str = "-";
+
+/// What the server tells a client, one message per line
+///
+/// Letters are sent as their index in the alphabet, given first.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerMessage {
+    /// How each letter is written, a letter is an index in it
+    Alphabet(Vec<String>),
+    /// The game starts, `me` is the index of the client's player
+    Start {
+        me : usize,
+        players : Vec<String>,
+    },
+    /// A player is asked to play
+    Turn {
+        player : usize,
+        round : u32,
+    },
+    /// Each spot of the board, row by row
+    Board(Vec<Option<Tile>>),
+    /// The tiles of the client's hand
+    Hand(Vec<Tile>),
+    /// The tiles left in the bag and the score of each player
+    Scores {
+        bag : usize,
+        scores : Vec<u32>,
+    },
+    /// It's the client's turn, it must send a move
+    Play,
+    /// The last move was refused, the client will be asked again
+    Error(String),
+    /// A move was added to the board, written in the standard notation
+    Placed {
+        player : usize,
+        score : u32,
+        notation : String,
+    },
+    Exchanged {
+        player : usize,
+        count : usize,
+    },
+    Passed {
+        player : usize,
+    },
+    /// The dictionnary refused the word of a move
+    Challenged {
+        player : usize,
+        word : String,
+    },
+    /// The game is over, the best player first
+    ///
+    /// The points of the tiles left are sent, not the tiles.
+    End(Vec<Standing>),
+}
+
+/// What a client tells the server, one message per line
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientMessage {
+    /// The name of the player, sent once connected
+    Name(String),
+    /// A move in the standard notation, like `8H HELLO`
+    Move(String),
+    /// The letters to exchange, `*` standing for a wildcard
+    Exchange(String),
+    Pass,
+}
+
+/// Make a name fit in one word
+pub fn sanitize_name(name : &str) -> String {
+    let name : Vec<&str> = name.split_whitespace().collect();
+    if name.is_empty() {
+        return "anonymous".to_string();
+    }
+    name.join("_")
+}
+
+/// Write a tile as `<letter>:<points>`, or `<letter>*` for a wildcard
+fn tile_token(tile : &Tile) -> String {
+    if tile.wildcard() {
+        format!("{}*", tile.letter())
+    }
+    else {
+        format!("{}:{}", tile.letter(), tile.points())
+    }
+}
+
+fn parse_tile(token : &str) -> Option<Tile> {
+    if let Some(letter) = token.strip_suffix('*') {
+        let mut tile = Tile::new(0, 0, true);
+        tile.set_wildcard(letter.parse::<Letter>().ok()?);
+        return Some(tile);
+    }
+    let mut parts = token.splitn(2, ':');
+    let letter = parts.next()?.parse::<Letter>().ok()?;
+    let points = parts.next()?.parse::<u8>().ok()?;
+    Some(Tile::new(letter, points, false))
+}
+
+/// Read a whole list of numbers, None if one of them is not
+fn parse_numbers<T : std::str::FromStr>(tokens : &[&str]) -> Option<Vec<T>> {
+    tokens.iter().map(|t| t.parse().ok()).collect()
+}
+
+fn join<T : ToString>(items : &[T]) -> String {
+    items.iter().map(|i| i.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Alphabet(labels) => write!(f, "ALPHABET {}", labels.join(" ")),
+            ServerMessage::Start { me, players } => write!(f, "START {} {}", me, players.join(" ")),
+            ServerMessage::Turn { player, round } => write!(f, "TURN {} {}", player, round),
+            ServerMessage::Board(spots) => {
+                let spots : Vec<String> = spots.iter()
+                    .map(|spot| spot.as_ref().map_or(EMPTY.to_string(), tile_token))
+                    .collect();
+                write!(f, "BOARD {}", spots.join(" "))
+            }
+            ServerMessage::Hand(tiles) => {
+                let tiles : Vec<String> = tiles.iter().map(tile_token).collect();
+                write!(f, "HAND {}", tiles.join(" "))
+            }
+            ServerMessage::Scores { bag, scores } => write!(f, "SCORES {} {}", bag, join(scores)),
+            ServerMessage::Play => write!(f, "PLAY"),
+            ServerMessage::Error(message) => write!(f, "ERROR {}", message),
+            ServerMessage::Placed { player, score, notation } =>
+                write!(f, "PLACED {} {} {}", player, score, notation),
+            ServerMessage::Exchanged { player, count } => write!(f, "EXCHANGED {} {}", player, count),
+            ServerMessage::Passed { player } => write!(f, "PASSED {}", player),
+            ServerMessage::Challenged { player, word } => write!(f, "CHALLENGED {} {}", player, word),
+            ServerMessage::End(standings) => {
+                let standings : Vec<String> = standings.iter()
+                    .map(|s| format!("{}:{}:{}:{}:{}", s.player, s.score, s.penalty, s.tiles, s.name))
+                    .collect();
+                write!(f, "END {}", standings.join(" "))
+            }
+        }
+    }
+}
+
+impl ServerMessage {
+    /// Read a line sent by the server
+    ///
+    /// # Return Value
+    /// None if the line is not a message.
+    pub fn parse(line : &str) -> Option<ServerMessage> {
+        let tokens : Vec<&str> = line.split_whitespace().collect();
+        let (keyword, args) = tokens.split_first()?;
+        let message = match (*keyword, args) {
+            ("ALPHABET", labels) => ServerMessage::Alphabet(labels.iter().map(|l| l.to_string()).collect()),
+            ("START", [me, players @ ..]) => ServerMessage::Start {
+                me : me.parse().ok()?,
+                players : players.iter().map(|p| p.to_string()).collect(),
+            },
+            ("TURN", [player, round]) => ServerMessage::Turn {
+                player : player.parse().ok()?,
+                round : round.parse().ok()?,
+            },
+            ("BOARD", spots) => ServerMessage::Board(spots.iter()
+                .map(|spot| if *spot == EMPTY { Some(None) } else { parse_tile(spot).map(Some) })
+                .collect::<Option<Vec<Option<Tile>>>>()?),
+            ("HAND", tiles) => ServerMessage::Hand(tiles.iter()
+                .map(|t| parse_tile(t)).collect::<Option<Vec<Tile>>>()?),
+            ("SCORES", [bag, scores @ ..]) => ServerMessage::Scores {
+                bag : bag.parse().ok()?,
+                scores : parse_numbers(scores)?,
+            },
+            ("PLAY", []) => ServerMessage::Play,
+            ("ERROR", _) => ServerMessage::Error(line.trim()["ERROR".len()..].trim().to_string()),
+            ("PLACED", [player, score, coordinates, word]) => ServerMessage::Placed {
+                player : player.parse().ok()?,
+                score : score.parse().ok()?,
+                notation : format!("{} {}", coordinates, word),
+            },
+            ("EXCHANGED", [player, count]) => ServerMessage::Exchanged {
+                player : player.parse().ok()?,
+                count : count.parse().ok()?,
+            },
+            ("PASSED", [player]) => ServerMessage::Passed { player : player.parse().ok()? },
+            ("CHALLENGED", [player, word]) => ServerMessage::Challenged {
+                player : player.parse().ok()?,
+                word : word.to_string(),
+            },
+            ("END", standings) => ServerMessage::End(standings.iter().map(|s| {
+                let parts : Vec<&str> = s.splitn(5, ':').collect();
+                match parts.as_slice() {
+                    [player, score, penalty, tiles, name] => Some(Standing {
+                        player : player.parse().ok()?,
+                        score : score.parse().ok()?,
+                        rack : Vec::new(),
+                        tiles : tiles.parse().ok()?,
+                        penalty : penalty.parse().ok()?,
+                        name : name.to_string(),
+                    }),
+                    _ => None,
+                }
+            }).collect::<Option<Vec<Standing>>>()?),
+            _ => return None,
+        };
+        Some(message)
+    }
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientMessage::Name(name) => write!(f, "NAME {}", name),
+            ClientMessage::Move(notation) => write!(f, "MOVE {}", notation),
+            ClientMessage::Exchange(letters) => write!(f, "EXCHANGE {}", letters),
+            ClientMessage::Pass => write!(f, "PASS"),
+        }
+    }
+}
+
+impl ClientMessage {
+    /// Read a line sent by a client
+    ///
+    /// # Return Value
+    /// None if the line is not a message.
+    pub fn parse(line : &str) -> Option<ClientMessage> {
+        let line = line.trim();
+        let (keyword, rest) = match line.find(char::is_whitespace) {
+            Some(index) => (&line[..index], line[index..].trim()),
+            None => (line, ""),
+        };
+        match (keyword, rest) {
+            ("NAME", name) => Some(ClientMessage::Name(sanitize_name(name))),
+            ("MOVE", notation) if !notation.is_empty() => Some(ClientMessage::Move(notation.to_string())),
+            ("EXCHANGE", letters) if !letters.is_empty() => Some(ClientMessage::Exchange(letters.to_string())),
+            ("PASS", "") => Some(ClientMessage::Pass),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ServerMessage, ClientMessage};
+    use crate::scrabbleutils::{Tile, Standing};
+
+    #[test]
+    fn round_trip() {
+        let mut wildcard = Tile::new(0, 0, true);
+        wildcard.set_wildcard(3);
+        let messages = vec![
+            ServerMessage::Alphabet(vec!["A".to_string(), "CH".to_string()]),
+            ServerMessage::Start { me : 1, players : vec!["ann".to_string(), "bob".to_string()] },
+            ServerMessage::Board(vec![None, Some(Tile::new(2, 4, false)), Some(wildcard.clone())]),
+            ServerMessage::Hand(vec![Tile::new(1, 1, false), wildcard]),
+            ServerMessage::Scores { bag : 86, scores : vec![12, 0] },
+            ServerMessage::Play,
+            ServerMessage::Error("HELO is not in the dictionnary.".to_string()),
+            ServerMessage::Placed { player : 0, score : 10, notation : "8H HELLO".to_string() },
+            ServerMessage::End(vec![Standing { player : 1, name : "bob".to_string(), score : 20,
+                rack : Vec::new(), tiles : -3, penalty : 10 }]),
+        ];
+        for message in messages {
+            assert_eq!(ServerMessage::parse(&message.to_string()), Some(message));
+        }
+        assert_eq!(ServerMessage::parse("TURN 1"), None);
+
+        assert_eq!(ClientMessage::parse("NAME Ann  Lee\n"), Some(ClientMessage::Name("Ann_Lee".to_string())));
+        assert_eq!(ClientMessage::parse("MOVE 8H HELLO"), Some(ClientMessage::Move("8H HELLO".to_string())));
+        assert_eq!(ClientMessage::parse("PASS"), Some(ClientMessage::Pass));
+        assert_eq!(ClientMessage::parse("MOVE"), None);
+    }
+}
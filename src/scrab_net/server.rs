@@ -0,0 +1,332 @@
+use crate::scrabbleutils::{PlayerTrait, GameView, Game, Hand, Action, Move, Tile, Alphabet,
+    Event, Observer, Standing, WILDCARD};
+use super::protocol::{ServerMessage, ClientMessage};
+use std::cell::Cell;
+use std::io::{self, prelude::*, BufReader};
+use std::net::TcpStream;
+use std::rc::Rc;
+
+/// The way the server writes to a client
+///
+/// Clones write to the same client.
+#[derive(Clone)]
+pub struct Connection {
+    stream : Rc<TcpStream>,
+    /// Set once the client is gone
+    closed : Rc<Cell<bool>>,
+}
+
+impl Connection {
+    fn new(stream : TcpStream) -> Connection {
+        Connection {
+            stream : Rc::new(stream),
+            closed : Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Send a message, the connection is closed if it can't be sent
+    pub fn send(&self, message : &ServerMessage) {
+        if self.is_closed() {
+            return;
+        }
+        let line = format!("{}\n", message);
+        if (&*self.stream).write_all(line.as_bytes()).is_err() {
+            self.close();
+        }
+    }
+
+    /// Whether the client is gone
+    pub fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+    fn close(&self) {
+        self.closed.set(true);
+    }
+}
+
+/// A player playing from another machine
+///
+/// It sends the state of the game to its client and reads the moves it
+/// answers. A client that leaves passes every turn.
+pub struct RemotePlayer {
+    name : String,
+    reader : BufReader<TcpStream>,
+    connection : Connection,
+    /// The letters of the game, to read the moves
+    alphabet : Alphabet,
+}
+
+impl RemotePlayer {
+    /// Greet a client and wait for its name
+    ///
+    /// The client is sent the alphabet and must answer `NAME <name>`.
+    ///
+    /// # Errors
+    /// If the client can't be read or doesn't give its name.
+    pub fn accept(stream : TcpStream, alphabet : Alphabet) -> io::Result<RemotePlayer> {
+        let connection = Connection::new(stream.try_clone()?);
+        let labels = alphabet.letters().map(|l| alphabet.label(l).to_string()).collect();
+        connection.send(&ServerMessage::Alphabet(labels));
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let name = match ClientMessage::parse(&line) {
+            Some(ClientMessage::Name(name)) => name,
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                "the client should give its name first")),
+        };
+        Ok(RemotePlayer {
+            name,
+            reader,
+            connection,
+            alphabet,
+        })
+    }
+
+    /// Get the way to write to the client
+    pub fn connection(&self) -> Connection {
+        self.connection.clone()
+    }
+
+    /// Read the next message of the client, None if it is gone
+    fn receive(&mut self) -> Option<ClientMessage> {
+        loop {
+            let mut line = String::new();
+            match self.reader.read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    self.connection.close();
+                    return None;
+                }
+                Ok(_) => {}
+            }
+            match ClientMessage::parse(&line) {
+                Some(message) => return Some(message),
+                None => self.connection.send(&ServerMessage::Error(
+                    format!("`{}` is not understood", line.trim()))),
+            }
+        }
+    }
+
+    /// Get the tiles written `letters`, `*` standing for a wildcard
+    ///
+    /// The game checks they are in the hand.
+    fn exchanged_tiles(&self, letters : &str) -> Option<Vec<Tile>> {
+        let wildcards = letters.chars().filter(|c| *c == WILDCARD).count();
+        let letters : String = letters.chars().filter(|c| *c != WILDCARD).collect();
+        let mut tiles : Vec<Tile> = self.alphabet.encode(&letters)?.into_iter()
+            .map(|letter| Tile::new(letter, 0, false))
+            .collect();
+        tiles.extend((0..wildcards).map(|_| Tile::new(0, 0, true)));
+        Some(tiles)
+    }
+}
+
+impl PlayerTrait for RemotePlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn play(&mut self, game : &GameView, hand : &Hand) -> Action {
+        let board = game.board();
+        let mut spots : Vec<Option<Tile>> = Vec::with_capacity(15 * 15);
+        for y in 0..15 {
+            for x in 0..15 {
+                spots.push(board.get_tile(x, y));
+            }
+        }
+        self.connection.send(&ServerMessage::Board(spots));
+        self.connection.send(&ServerMessage::Hand(hand.get()));
+        self.connection.send(&ServerMessage::Scores {
+            bag : game.bag_len(),
+            scores : (0..game.player_count()).map(|p| game.score(p)).collect(),
+        });
+        loop {
+            if self.connection.is_closed() {
+                return Action::Pass;
+            }
+            self.connection.send(&ServerMessage::Play);
+            let error = match self.receive() {
+                None => return Action::Pass,
+                Some(ClientMessage::Pass) => return Action::Pass,
+                Some(ClientMessage::Move(notation)) => match Move::from_notation(&notation, &self.alphabet) {
+                    Some(mv) => return Action::Place(mv),
+                    None => format!("`{}` is not a move like `8H HELLO`", notation),
+                },
+                Some(ClientMessage::Exchange(letters)) => match self.exchanged_tiles(&letters) {
+                    Some(tiles) => return Action::Exchange(tiles),
+                    None => format!("`{}` has letters that are not in the game", letters),
+                },
+                Some(ClientMessage::Name(_)) => "the name is already given".to_string(),
+            };
+            self.connection.send(&ServerMessage::Error(error));
+        }
+    }
+
+    fn move_score(&mut self, _score : u32) {
+    }
+
+    fn total_score(&mut self, _score : u32) {
+    }
+
+    fn new_game(&mut self, game : &GameView) {
+        self.connection.send(&ServerMessage::Start {
+            me : game.me(),
+            players : (0..game.player_count()).map(|p| game.name(p).to_string()).collect(),
+        });
+    }
+}
+
+/// Sends the events of a game to every client
+pub struct Broadcaster {
+    connections : Vec<Connection>,
+    /// The letters of the game, to write the moves
+    alphabet : Alphabet,
+}
+
+impl Broadcaster {
+    pub fn new(connections : Vec<Connection>, alphabet : Alphabet) -> Broadcaster {
+        Broadcaster {
+            connections,
+            alphabet,
+        }
+    }
+}
+
+impl Observer for Broadcaster {
+    fn notify(&self, event : &Event) {
+        let message = match event {
+            // Each player is told its own index when the game starts
+            Event::GameStart { .. } => return,
+            Event::TurnStart { player, round, .. } => ServerMessage::Turn { player : *player, round : *round },
+            Event::Placed { player, mv, breakdown } => ServerMessage::Placed {
+                player : *player,
+                score : breakdown.total(),
+                notation : mv.notation(&self.alphabet),
+            },
+            Event::Exchanged { player, count } => ServerMessage::Exchanged { player : *player, count : *count },
+            Event::Passed { player } => ServerMessage::Passed { player : *player },
+            Event::Challenged { player, word } => ServerMessage::Challenged {
+                player : *player,
+                word : word.clone(),
+            },
+            Event::GameEnd { standings } => ServerMessage::End(standings.clone()),
+        };
+        for connection in self.connections.iter() {
+            connection.send(&message);
+        }
+    }
+}
+
+/// Play a game until it is over or every client is gone
+///
+/// # Arguments
+/// * `game` - The game, its first players are the clients.
+/// * `connections` - The connection to each client, in the order they play.
+///
+/// # Return Value
+/// Where each player stands, the best first.
+pub fn host(game : &mut Game, connections : &[Connection]) -> Vec<Standing> {
+    while !game.is_over() {
+        if !connections.is_empty() && connections.iter().all(|c| c.is_closed()) {
+            break;
+        }
+        let current = game.current();
+        if let Err(e) = game.play_turn() {
+            if let Some(connection) = connections.get(current) {
+                connection.send(&ServerMessage::Error(e.to_string()));
+            }
+        }
+    }
+    game.end()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RemotePlayer, Broadcaster, host};
+    use crate::scrabbleutils::{Game, TileSet, Dico};
+    use std::io::{prelude::*, BufReader};
+    use std::net::{TcpListener, TcpStream};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    /// A client sending `name`, then answering each `PLAY` with `answer`
+    fn client(address : std::net::SocketAddr, name : &'static str,
+            answer : fn(&str, usize) -> String) -> thread::JoinHandle<Vec<String>> {
+        let stream = TcpStream::connect(address).unwrap();
+        thread::spawn(move || {
+            stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            writeln!(writer, "NAME {}", name).unwrap();
+            let mut lines : Vec<String> = Vec::new();
+            let mut hand = String::new();
+            let mut plays = 0;
+            for line in BufReader::new(stream).lines() {
+                let line = line.unwrap();
+                if line.starts_with("HAND") {
+                    hand = line.clone();
+                }
+                if line == "PLAY" {
+                    writeln!(writer, "{}", answer(&hand, plays)).unwrap();
+                    plays += 1;
+                }
+                let end = line.starts_with("END");
+                lines.push(line);
+                if end {
+                    break;
+                }
+            }
+            lines
+        })
+    }
+
+    #[test]
+    fn loopback_game() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            // Both hands take the whole bag, the first move goes out
+            let tileset = TileSet::from_reader("rack 2\nA 2 1\nT 2 1\n".as_bytes()).unwrap();
+            let alphabet = tileset.alphabet().clone();
+            let dico = Arc::new(Dico::from_words(vec!["AA", "AT", "TA", "TT"], &alphabet));
+            let mut game = Game::new(&tileset, dico);
+            let mut connections = Vec::new();
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let remote = RemotePlayer::accept(stream, alphabet.clone()).unwrap();
+                connections.push(remote.connection());
+                game.add_player(Box::new(remote));
+            }
+            game.add_observer(Rc::new(Broadcaster::new(connections.clone(), alphabet)));
+            game.set_first(0);
+            host(&mut game, &connections).len()
+        });
+
+        // Any two letters of the hand make a word
+        let ann = client(address, "ann", |hand, plays| match plays {
+            0 => "MOVE 8H ZZ".to_string(),
+            1 => "MOVE 8H AAA".to_string(),
+            _ => {
+                let word : String = hand.split_whitespace().skip(1)
+                    .map(|tile| if tile.starts_with("0:") { 'A' } else { 'T' })
+                    .collect();
+                format!("MOVE 8H {}", word)
+            }
+        });
+        let bob = client(address, "bob", |_, _| "PASS".to_string());
+
+        assert_eq!(server.join().unwrap(), 2);
+        let ann = ann.join().unwrap();
+        let bob = bob.join().unwrap();
+        assert_eq!(ann[0], "ALPHABET A T");
+        assert_eq!(ann[1], "START 0 ann bob");
+        assert!(ann.iter().any(|l| l.starts_with("ERROR `8H ZZ`")));
+        assert!(ann.contains(&"CHALLENGED 0 AAA".to_string()));
+        assert!(ann.contains(&"ERROR AAA is not in the dictionnary.".to_string()));
+        assert!(bob.iter().any(|l| l.starts_with("PLACED 0 4 8H")));
+        // Ann gets the points of the tiles Bob is left with
+        assert_eq!(bob.last().unwrap(), "END 0:6:0:2:ann 1:0:0:-2:bob");
+    }
+}
@@ -0,0 +1,10 @@
+/// What a player knows of each word, kept in a file
+mod record;
+/// Racks to find the words of
+mod quiz;
+/// Words ranked by how likely they are to be drawn
+mod probability;
+
+pub use record::{StudyRecord, WordStats, StudyError};
+pub use quiz::{Quiz, Question, Answer};
+pub use probability::{WordList, RankedWord, AlphagramGroup};
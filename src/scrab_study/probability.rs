@@ -0,0 +1,260 @@
+use crate::scrabbleutils::{Dico, TileSet, Letter};
+use std::io;
+
+/// Get how many ways `k` tiles can be taken out of `n`
+///
+/// # Return Value
+/// None if there are too many to count.
+fn choose(n : u64, k : u64) -> Option<u64> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    // Each step stays a whole number: it is the count for i + 1 tiles
+    (0..k).try_fold(1u64, |ways, i| Some(ways.checked_mul(n - i)? / (i + 1)))
+}
+
+/// How many tiles of each letter a tileset has, and how many wildcards
+struct Counts {
+    letters : Vec<u64>,
+    wildcards : u64,
+}
+
+impl Counts {
+    fn new(tileset : &TileSet) -> Counts {
+        let mut letters = vec![0; tileset.alphabet().len()];
+        let mut wildcards = 0;
+        for info in tileset.infos() {
+            let tile = info.tile();
+            if tile.wildcard() {
+                wildcards += info.occurences() as u64;
+            }
+            else {
+                letters[tile.letter() as usize] += info.occurences() as u64;
+            }
+        }
+        Counts { letters, wildcards }
+    }
+
+    /// Get how many different draws of the tiles give the letters of `word`
+    ///
+    /// Wildcards can stand for any of the letters: the draws are counted for
+    /// each number of wildcards used, and for each way to share them among
+    /// the letters.
+    ///
+    /// # Return Value
+    /// None if there are too many to count.
+    fn combinations(&self, word : &[Letter]) -> Option<u64> {
+        let mut needed : Vec<(Letter, u64)> = Vec::new();
+        for letter in word {
+            match needed.iter_mut().find(|e| e.0 == *letter) {
+                Some(e) => e.1 += 1,
+                None => needed.push((*letter, 1)),
+            }
+        }
+        // The ways to draw the letters seen so far, by number of wildcards
+        // standing for some of them
+        let mut ways : Vec<u64> = vec![1];
+        for (letter, count) in needed {
+            let available = self.letters.get(letter as usize).copied().unwrap_or(0);
+            let mut next : Vec<u64> = vec![0; ways.len() + count as usize];
+            for (blanks, w) in ways.iter().enumerate() {
+                for replaced in 0..=count {
+                    let draws = w.checked_mul(choose(available, count - replaced)?)?;
+                    next[blanks + replaced as usize] = next[blanks + replaced as usize].checked_add(draws)?;
+                }
+            }
+            ways = next;
+        }
+        ways.iter().enumerate()
+            .try_fold(0u64, |sum, (blanks, w)| sum.checked_add(w.checked_mul(choose(self.wildcards, blanks as u64)?)?))
+    }
+}
+
+/// A word and how likely it is to be drawn
+pub struct RankedWord {
+    pub word : String,
+    /// The letters of the word, in the order of the alphabet
+    pub alphagram : String,
+    /// How many different draws of as many tiles give the letters
+    pub combinations : u64,
+    /// The place of the word in the list, starting at 1, the same for words
+    /// as likely
+    pub rank : usize,
+}
+
+/// The words sharing an alphagram
+pub struct AlphagramGroup {
+    pub alphagram : String,
+    pub words : Vec<String>,
+    pub combinations : u64,
+    pub rank : usize,
+}
+
+/// The words of a length, the most likely to be drawn first
+///
+/// The likelier a word, the more often it can be made from a rack. Words
+/// just as likely are in alphabetical order.
+pub struct WordList {
+    length : usize,
+    /// How many different draws of `length` tiles there are
+    draws : u64,
+    words : Vec<RankedWord>,
+}
+
+/// Give the same rank to what comes in a row with as many combinations
+fn rank<T>(items : &mut [T], combinations : impl Fn(&T) -> u64, set_rank : impl Fn(&mut T, usize)) {
+    let mut rank = 0;
+    let mut previous = None;
+    for (index, item) in items.iter_mut().enumerate() {
+        if previous != Some(combinations(item)) {
+            rank = index + 1;
+            previous = Some(combinations(item));
+        }
+        set_rank(item, rank);
+    }
+}
+
+impl WordList {
+    /// List the words of `dico` with `length` letters
+    ///
+    /// A letter written with several characters, like `CH`, counts as one.
+    /// The list is empty when the draws of `length` tiles are too many to
+    /// count, which only happens for words longer than the board.
+    pub fn new(dico : &Dico, tileset : &TileSet, length : usize) -> WordList {
+        let alphabet = tileset.alphabet();
+        let counts = Counts::new(tileset);
+        let total = counts.letters.iter().sum::<u64>() + counts.wildcards;
+        let draws = match choose(total, length as u64) {
+            Some(draws) => draws,
+            None => return WordList { length, draws : 0, words : Vec::new() },
+        };
+        let pattern = "?".repeat(length);
+        let mut words : Vec<RankedWord> = dico.matching(&pattern).into_iter().flatten()
+            .filter_map(|word| {
+                let mut letters = alphabet.encode(&word)?;
+                letters.sort_unstable();
+                Some(RankedWord {
+                    alphagram : alphabet.decode(&letters),
+                    combinations : counts.combinations(&letters)?,
+                    word,
+                    rank : 0,
+                })
+            })
+            .collect();
+        words.sort_by(|a, b| b.combinations.cmp(&a.combinations).then_with(|| a.word.cmp(&b.word)));
+        rank(&mut words, |w| w.combinations, |w, rank| w.rank = rank);
+        WordList {
+            length,
+            draws,
+            words,
+        }
+    }
+
+    pub fn words(&self) -> &[RankedWord] {
+        &self.words
+    }
+
+    /// Get the chance of drawing the letters of `word` in a rack of as many
+    /// tiles, from a full bag
+    pub fn probability(&self, word : &RankedWord) -> f64 {
+        if self.draws == 0 {
+            return 0.0;
+        }
+        word.combinations as f64 / self.draws as f64
+    }
+
+    /// Group the anagrams together, the most likely alphagrams first
+    ///
+    /// The words of a group are in alphabetical order.
+    pub fn groups(&self) -> Vec<AlphagramGroup> {
+        let mut groups : Vec<AlphagramGroup> = Vec::new();
+        for word in self.words.iter() {
+            match groups.iter_mut().find(|g| g.alphagram == word.alphagram) {
+                Some(group) => group.words.push(word.word.clone()),
+                None => groups.push(AlphagramGroup {
+                    alphagram : word.alphagram.clone(),
+                    words : vec![word.word.clone()],
+                    combinations : word.combinations,
+                    rank : 0,
+                }),
+            }
+        }
+        for group in groups.iter_mut() {
+            group.words.sort();
+        }
+        groups.sort_by(|a, b| b.combinations.cmp(&a.combinations).then_with(|| a.alphagram.cmp(&b.alphagram)));
+        rank(&mut groups, |g| g.combinations, |g, rank| g.rank = rank);
+        groups
+    }
+
+    /// Write a word a line, with its rank, alphagram, combinations and
+    /// probability in percent
+    pub fn to_text(&self) -> String {
+        let mut text = format!("# {} letter words: rank word alphagram combinations probability\n", self.length);
+        for word in self.words.iter() {
+            text.push_str(&format!("{} {} {} {} {:.6}%\n", word.rank, word.word, word.alphagram,
+                word.combinations, self.probability(word) * 100.0));
+        }
+        text
+    }
+
+    /// Write an alphagram a line, with its rank, combinations and words
+    pub fn groups_to_text(&self) -> String {
+        let mut text = format!("# {} letter alphagrams: rank alphagram combinations words\n", self.length);
+        for group in self.groups() {
+            text.push_str(&format!("{} {} {} {}\n", group.rank, group.alphagram, group.combinations,
+                group.words.join(" ")));
+        }
+        text
+    }
+
+    /// Save the list in a file, grouped by alphagram if `groups`
+    ///
+    /// # Errors
+    /// If the file can't be written.
+    pub fn save(&self, filename : &str, groups : bool) -> io::Result<()> {
+        let text = if groups { self.groups_to_text() } else { self.to_text() };
+        std::fs::write(filename, text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WordList, Counts, choose};
+    use crate::scrabbleutils::{Dico, TileSet};
+
+    #[test]
+    fn probability() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        assert_eq!(choose(100, 7), Some(16_007_560_800));
+        assert_eq!(choose(100, 30), None);
+        // 2 M and 12 E, or a blank for one of them, or both
+        let counts = Counts::new(&tileset);
+        assert_eq!(counts.combinations(&ab.encode("ME").unwrap()), Some(2 * 12 + 2 * 2 + 2 * 12 + 1));
+        // Only one Z: the second one has to be a blank
+        assert_eq!(counts.combinations(&ab.encode("ZZ").unwrap()), Some(2 + 1));
+
+        let dico = Dico::from_words(vec!["ZA", "AE", "EA", "IT", "TI", "ME", "QI", "HELLO"], ab);
+        let list = WordList::new(&dico, &tileset, 2);
+        let words : Vec<&str> = list.words().iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["AE", "EA", "IT", "TI", "ME", "QI", "ZA"]);
+        assert_eq!(list.words()[1].rank, 1);
+        assert_eq!(list.words()[2].rank, 3);
+        let total : f64 = list.words().iter().map(|w| list.probability(w)).sum();
+        assert!(total > 0.0 && total < 1.0);
+
+        let groups = list.groups();
+        assert_eq!(groups.len(), 5);
+        assert_eq!((groups[0].alphagram.as_str(), &groups[0].words[..]), ("AE", &["AE".to_string(), "EA".to_string()][..]));
+        assert!(list.groups_to_text().contains("\n2 IT 85 IT TI\n"));
+        assert!(list.to_text().contains("\n5 ME EM 53 "));
+        assert_eq!((list.words()[5].rank, list.words()[6].rank), (6, 6));
+
+        // Longer than the board, the draws can't be counted
+        let long = "ABCDEFGHIJKLMNOPQRSTU";
+        let dico = Dico::from_words(vec![long], ab);
+        assert!(WordList::new(&dico, &tileset, long.len()).words().is_empty());
+    }
+}
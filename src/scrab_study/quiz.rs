@@ -0,0 +1,173 @@
+use crate::scrabbleutils::{Dico, TileSet, Letter};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use super::StudyRecord;
+
+/// How many racks are drawn to find one making a word, before giving up
+const DRAW_ATTEMPTS : usize = 1000;
+
+/// A rack to find every word of
+pub struct Question {
+    /// The letters, in alphabetical order
+    pub rack : String,
+    /// Every word using all the letters
+    pub answers : Vec<String>,
+}
+
+/// What the player found for a question
+pub struct Answer {
+    pub found : Vec<String>,
+    pub missed : Vec<String>,
+    /// What was given and is not a word of the rack
+    pub wrong : Vec<String>,
+}
+
+/// Asks for the words of racks, the hard words again when they are due
+///
+/// The racks are drawn the way hands are, from the tiles of a `TileSet`
+/// without the wildcards.
+pub struct Quiz<'a> {
+    tileset : &'a TileSet,
+    dico : &'a Dico,
+    record : StudyRecord,
+    /// How many letters the drawn racks have
+    length : usize,
+    rng : StdRng,
+    /// The racks already asked, they are not asked twice in a session
+    asked : Vec<String>,
+}
+
+impl<'a> Quiz<'a> {
+    /// Create a quiz
+    ///
+    /// # Arguments
+    /// * `tileset` - The tiles the racks are drawn from.
+    /// * `dico` - The words to find.
+    /// * `record` - What the player knows, updated with the answers.
+    /// * `length` - The length of the racks drawn, the rack size for bingos.
+    pub fn new(tileset : &'a TileSet, dico : &'a Dico, record : StudyRecord, length : usize) -> Quiz<'a> {
+        Quiz {
+            tileset,
+            dico,
+            record,
+            length,
+            rng : StdRng::from_entropy(),
+            asked : Vec::new(),
+        }
+    }
+
+    /// Draw the same racks for the same `seed`
+    pub fn set_seed(&mut self, seed : u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Get what the player knows, with the answers given so far
+    pub fn record(&self) -> &StudyRecord {
+        &self.record
+    }
+
+    /// Make the question for the letters of `rack`
+    ///
+    /// # Return Value
+    /// None if a letter is not in the game.
+    pub fn question(&self, rack : &str) -> Option<Question> {
+        let alphabet = self.tileset.alphabet();
+        let mut letters = alphabet.encode(rack)?;
+        letters.sort_unstable();
+        let rack = alphabet.decode(&letters);
+        let answers = self.dico.anagrams(&rack).collect();
+        Some(Question { rack, answers })
+    }
+
+    /// Get the next question
+    ///
+    /// The words due on the day `today` come first, then racks are drawn
+    /// until one makes a word.
+    ///
+    /// # Return Value
+    /// None if no rack making a word was found.
+    pub fn next_question(&mut self, today : u64) -> Option<Question> {
+        let due : Vec<String> = self.record.due(today).into_iter().map(|w| w.to_string()).collect();
+        for word in due {
+            match self.question(&word) {
+                Some(question) if !self.asked.contains(&question.rack) => {
+                    self.asked.push(question.rack.clone());
+                    return Some(question);
+                }
+                _ => {}
+            }
+        }
+        let bag : Vec<Letter> = self.tileset.infos().iter()
+            .filter(|info| !info.tile().wildcard())
+            .flat_map(|info| std::iter::repeat_n(info.tile().letter(), info.occurences() as usize))
+            .collect();
+        for _ in 0..DRAW_ATTEMPTS {
+            let rack : Vec<Letter> = bag.choose_multiple(&mut self.rng, self.length).cloned().collect();
+            let question = self.question(&self.tileset.alphabet().decode(&rack))?;
+            if !question.answers.is_empty() && !self.asked.contains(&question.rack) {
+                self.asked.push(question.rack.clone());
+                return Some(question);
+            }
+        }
+        None
+    }
+
+    /// Check the words the player gave for `question` on the day `today`
+    ///
+    /// Each word of the rack is recorded as found or missed.
+    pub fn answer(&mut self, question : &Question, guesses : &[&str], today : u64) -> Answer {
+        let alphabet = self.tileset.alphabet();
+        let mut found : Vec<String> = Vec::new();
+        let mut wrong : Vec<String> = Vec::new();
+        for guess in guesses {
+            // Written the way the dictionnary does
+            let word = alphabet.encode(guess).map_or(guess.to_uppercase(), |w| alphabet.decode(&w));
+            if question.answers.contains(&word) {
+                if !found.contains(&word) {
+                    found.push(word);
+                }
+            }
+            else if !wrong.contains(&word) {
+                wrong.push(word);
+            }
+        }
+        let missed : Vec<String> = question.answers.iter().filter(|w| !found.contains(w)).cloned().collect();
+        for word in question.answers.iter() {
+            self.record.answer(word, found.contains(word), today);
+        }
+        Answer { found, missed, wrong }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Quiz;
+    use super::super::StudyRecord;
+    use crate::scrabbleutils::{Dico, TileSet};
+
+    #[test]
+    fn quiz() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let dico = Dico::from_words(vec!["STAR", "RATS", "ARTS", "TSAR", "IT", "TI"], tileset.alphabet());
+        let mut quiz = Quiz::new(&tileset, &dico, StudyRecord::new(), 2);
+        quiz.set_seed(7);
+
+        let question = quiz.question("rast").unwrap();
+        assert_eq!(question.rack, "ARST");
+        assert_eq!(question.answers, vec!["ARTS", "RATS", "STAR", "TSAR"]);
+        let answer = quiz.answer(&question, &["star", "RATS", "TRAS", "star"], 3);
+        assert_eq!(answer.found, vec!["STAR", "RATS"]);
+        assert_eq!(answer.missed, vec!["ARTS", "TSAR"]);
+        assert_eq!(answer.wrong, vec!["TRAS"]);
+        assert_eq!(quiz.record().stats("TSAR").unwrap().due, 4);
+
+        // The missed words come back the next day, once
+        let again = quiz.next_question(4).unwrap();
+        assert_eq!(again.rack, "ARST");
+        // Then a drawn rack: only IT and TI make words with 2 letters
+        let drawn = quiz.next_question(4).unwrap();
+        assert_eq!(drawn.rack, "IT");
+        assert!(quiz.next_question(4).is_none());
+    }
+}
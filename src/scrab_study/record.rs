@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader};
+
+/// Starts a comment in a study file
+const COMMENT : char = '#';
+/// The highest level a word can reach, it is then asked every 2^5 days
+const MAX_LEVEL : u32 = 5;
+
+/// How well a word is known
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WordStats {
+    /// How many times the word was asked
+    pub seen : u32,
+    /// How many times it was found
+    pub found : u32,
+    /// Goes up each time the word is found in a row, back to 0 when it is
+    /// missed
+    pub level : u32,
+    /// The day the word should be asked again, counted from 1970
+    pub due : u64,
+}
+
+impl WordStats {
+    /// Get the share of the times the word was found, in percent
+    pub fn accuracy(&self) -> f64 {
+        if self.seen == 0 {
+            return 0.0;
+        }
+        self.found as f64 * 100.0 / self.seen as f64
+    }
+}
+
+/// Why a study file could not be read
+#[derive(Debug)]
+pub enum StudyError {
+    /// The file could not be read
+    Io(io::Error),
+    /// A line is malformed, starting at 1
+    Syntax {
+        line : usize,
+        message : String,
+    },
+}
+
+impl fmt::Display for StudyError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StudyError::Io(e) => write!(f, "{}", e),
+            StudyError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl From<io::Error> for StudyError {
+    fn from(e : io::Error) -> StudyError {
+        StudyError::Io(e)
+    }
+}
+
+/// What a player knows of each word, to ask the hard ones again
+///
+/// A word found goes up a level and is asked again 2^level days later, a
+/// word missed goes back to level 0 and is asked again the next day.
+#[derive(Default)]
+pub struct StudyRecord {
+    /// Sorted to be saved the same way every time
+    words : BTreeMap<String, WordStats>,
+}
+
+impl StudyRecord {
+    pub fn new() -> StudyRecord {
+        StudyRecord::default()
+    }
+
+    /// Read a record from a file, an empty one if there is no file yet
+    ///
+    /// See `from_reader()` for the format.
+    ///
+    /// # Errors
+    /// If the file can't be read or is malformed.
+    pub fn from_file(filename : &str) -> Result<StudyRecord, StudyError> {
+        match File::open(filename) {
+            Ok(file) => StudyRecord::from_reader(BufReader::new(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(StudyRecord::new()),
+            Err(e) => Err(StudyError::Io(e)),
+        }
+    }
+
+    /// Read a record
+    ///
+    /// Each line is a word then how many times it was asked, found, its
+    /// level and the day it is due, separated by spaces. Blank lines are
+    /// ignored and `#` starts a comment.
+    ///
+    /// # Errors
+    /// If a line is malformed.
+    pub fn from_reader<R : BufRead>(reader : R) -> Result<StudyRecord, StudyError> {
+        let mut record = StudyRecord::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.split(COMMENT).next().unwrap_or("");
+            let fields : Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() {
+                continue;
+            }
+            let syntax = |message : &str| StudyError::Syntax { line : index + 1, message : message.to_string() };
+            let numbers : Vec<u64> = match fields[1..].iter().map(|f| f.parse()).collect() {
+                Ok(numbers) => numbers,
+                Err(_) => return Err(syntax("the counts should be numbers")),
+            };
+            let stats = match numbers[..] {
+                [seen, found, level, due] if found <= seen => WordStats {
+                    seen : seen as u32,
+                    found : found as u32,
+                    level : (level as u32).min(MAX_LEVEL),
+                    due,
+                },
+                [_, _, _, _] => return Err(syntax("a word can't be found more times than it was asked")),
+                _ => return Err(syntax("a line should be a word and 4 numbers")),
+            };
+            record.words.insert(fields[0].to_uppercase(), stats);
+        }
+        Ok(record)
+    }
+
+    /// Write the record the way `from_reader()` reads it
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# word seen found level due\n");
+        for (word, stats) in self.words.iter() {
+            text.push_str(&format!("{} {} {} {} {}\n", word, stats.seen, stats.found, stats.level, stats.due));
+        }
+        text
+    }
+
+    /// Save the record in a file
+    ///
+    /// # Errors
+    /// If the file can't be written.
+    pub fn save(&self, filename : &str) -> io::Result<()> {
+        std::fs::write(filename, self.to_text())
+    }
+
+    /// Get what is known of `word`, None if it was never asked
+    pub fn stats(&self, word : &str) -> Option<&WordStats> {
+        self.words.get(word)
+    }
+
+    /// Tell whether `word` was found when it was asked on the day `today`
+    pub fn answer(&mut self, word : &str, found : bool, today : u64) {
+        let stats = self.words.entry(word.to_string()).or_default();
+        stats.seen += 1;
+        if found {
+            stats.found += 1;
+            stats.level = (stats.level + 1).min(MAX_LEVEL);
+        }
+        else {
+            stats.level = 0;
+        }
+        stats.due = today + (1 << stats.level);
+    }
+
+    /// Get the words to ask again on the day `today`
+    ///
+    /// # Return Value
+    /// The words, the less often found first.
+    pub fn due(&self, today : u64) -> Vec<&str> {
+        let mut due : Vec<(&String, &WordStats)> = self.words.iter()
+            .filter(|(_, stats)| stats.due <= today)
+            .collect();
+        due.sort_by(|a, b| a.1.accuracy().partial_cmp(&b.1.accuracy()).unwrap_or(std::cmp::Ordering::Equal));
+        due.into_iter().map(|(word, _)| word.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{StudyRecord, StudyError};
+
+    #[test]
+    fn spaced_repetition() {
+        let mut record = StudyRecord::new();
+        record.answer("HELLO", true, 10);
+        record.answer("WORLD", false, 10);
+        record.answer("MARIO", true, 10);
+        record.answer("MARIO", false, 11);
+        assert_eq!(record.stats("HELLO").unwrap().due, 12);
+        assert_eq!(record.stats("WORLD").unwrap().due, 11);
+        assert!(record.due(10).is_empty());
+        // MARIO was found once out of twice, WORLD never
+        assert_eq!(record.due(12), vec!["WORLD", "MARIO", "HELLO"]);
+
+        let text = record.to_text();
+        assert!(text.contains("MARIO 2 1 0 12\n"));
+        let read = StudyRecord::from_reader(text.as_bytes()).unwrap();
+        assert_eq!(read.to_text(), text);
+        assert_eq!(read.stats("MARIO").unwrap().accuracy(), 50.0);
+
+        let wrong = StudyRecord::from_reader("HELLO 1 1 1 3\nWORLD 1 2 0 3\n".as_bytes());
+        assert!(matches!(wrong, Err(StudyError::Syntax { line : 2, .. })));
+        assert!(StudyRecord::from_reader("HELLO 1 x 1 3\n".as_bytes()).is_err());
+        assert!(StudyRecord::from_reader("HELLO 1\n".as_bytes()).is_err());
+    }
+}
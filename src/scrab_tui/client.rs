@@ -0,0 +1,178 @@
+use crate::scrabbleutils::{Board, Hand, Alphabet, Move, Action, Tile, WILDCARD};
+use crate::scrab_net::{ServerMessage, ClientMessage, sanitize_name};
+use super::{print_board, ask_move, scores_line, handle_error};
+use std::io::{self, prelude::*, BufReader};
+use std::net::TcpStream;
+
+/// What the client knows about the game, from the server
+struct State {
+    alphabet : Alphabet,
+    names : Vec<String>,
+    board : Board,
+    hand : Hand,
+    status : String,
+}
+
+impl State {
+    fn name(&self, player : usize) -> &str {
+        self.names.get(player).map_or("?", |name| name.as_str())
+    }
+}
+
+/// Write tiles to exchange the way the server reads them, `*` for a wildcard
+fn exchange_letters(tiles : &[Tile], alphabet : &Alphabet) -> String {
+    let letters : Vec<_> = tiles.iter().filter(|t| !t.wildcard()).map(|t| t.letter()).collect();
+    let wildcards = tiles.iter().filter(|t| t.wildcard()).count();
+    alphabet.decode(&letters) + &WILDCARD.to_string().repeat(wildcards)
+}
+
+/// Play on a game server
+///
+/// The state of the game comes from the server, the moves are asked like for
+/// a local game and sent back.
+///
+/// # Arguments
+/// * `stream` - The connection to the server.
+/// * `name` - The name of the player.
+/// * `input` - Where the moves are read from, the standard input usually.
+///
+/// # Errors
+/// If the server can't be read or written to.
+pub fn run_client<R : BufRead>(stream : TcpStream, name : &str, mut input : R) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    writeln!(writer, "{}", ClientMessage::Name(sanitize_name(name)))?;
+    let mut state = State {
+        alphabet : Alphabet::new(Vec::new()),
+        names : Vec::new(),
+        board : Board::new(),
+        hand : Hand::new(),
+        status : String::new(),
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let message = match ServerMessage::parse(&line) {
+            Some(message) => message,
+            None => {
+                handle_error(&format!("The server sent `{}`", line));
+                continue;
+            }
+        };
+        match message {
+            ServerMessage::Alphabet(labels) => state.alphabet = Alphabet::new(labels),
+            ServerMessage::Start { players, .. } => state.names = players,
+            ServerMessage::Turn { player, round } => {
+                println!("Round {}, {} is playing", round, state.name(player));
+            }
+            ServerMessage::Board(spots) => {
+                for (i, tile) in spots.into_iter().enumerate().take(15 * 15) {
+                    state.board.set_tile((i % 15) as u8, (i / 15) as u8, tile);
+                }
+            }
+            ServerMessage::Hand(tiles) => state.hand = Hand::from_tiles(tiles),
+            ServerMessage::Scores { bag, scores } => {
+                state.status = scores_line(&state.names, &scores, bag);
+            }
+            ServerMessage::Play => {
+                // The server has the dictionnary, only the letters are checked here
+                let (board, hand) = (&state.board, &state.hand);
+                let preview = |mv : &Move| {
+                    let letters = board.needed_letters(mv);
+                    match Hand::from_tiles(hand.get()).remove(&letters) {
+                        Some(removed) if board.can_place(mv) => Ok(board.score_breakdown(mv, &removed)),
+                        _ => Err("This can't be made with your letters".to_string()),
+                    }
+                };
+                let answer = match ask_move(&mut input, board, hand, &state.alphabet, &state.status,
+                        &preview, None) {
+                    Some(Action::Place(mv)) => ClientMessage::Move(mv.notation(&state.alphabet)),
+                    Some(Action::Exchange(tiles)) => ClientMessage::Exchange(exchange_letters(&tiles, &state.alphabet)),
+                    Some(Action::Pass) | None => ClientMessage::Pass,
+                };
+                writeln!(writer, "{}", answer)?;
+            }
+            ServerMessage::Error(error) => handle_error(&error),
+            ServerMessage::Placed { player, score, notation } => {
+                println!("{} played {} for {} points", state.name(player), notation, score);
+            }
+            ServerMessage::Exchanged { player, count } => {
+                println!("{} exchanged {} tiles", state.name(player), count);
+            }
+            ServerMessage::Passed { player } => println!("{} passed", state.name(player)),
+            ServerMessage::Challenged { player, word } => {
+                println!("{} tried {}, which is not in the dictionnary", state.name(player), word);
+            }
+            ServerMessage::End(standings) => {
+                print_board(&state.board, &state.alphabet);
+                for (rank, standing) in standings.iter().enumerate() {
+                    println!("{}. {} : {} points", rank + 1, standing.name, standing.score);
+                }
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run_client, exchange_letters};
+    use crate::scrabbleutils::{Game, TileSet, Dico, Alphabet, Tile};
+    use crate::scrab_net::{RemotePlayer, Broadcaster, host};
+    use std::io::{prelude::*, BufReader};
+    use std::net::{TcpListener, TcpStream};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn plays_on_a_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            // Both hands take the whole bag, AA goes out
+            let tileset = TileSet::from_reader("rack 2\nA 4 1\n".as_bytes()).unwrap();
+            let alphabet = tileset.alphabet().clone();
+            let dico = Arc::new(Dico::from_words(vec!["AA"], &alphabet));
+            let mut game = Game::new(&tileset, dico);
+            let mut connections = Vec::new();
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().unwrap();
+                let remote = RemotePlayer::accept(stream, alphabet.clone()).unwrap();
+                connections.push(remote.connection());
+                game.add_player(Box::new(remote));
+            }
+            game.add_observer(Rc::new(Broadcaster::new(connections.clone(), alphabet)));
+            game.set_first(0);
+            host(&mut game, &connections)
+        });
+
+        let client = thread::spawn(move || {
+            let stream = TcpStream::connect(address).unwrap();
+            // A pass, a badly written move, then AA at the center
+            run_client(stream, "Ann Lee", "-\nAA\n8\nAA\n8 8\nH\n".as_bytes()).unwrap();
+        });
+        // The other player passes
+        let stream = TcpStream::connect(address).unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        writeln!(writer, "NAME bob").unwrap();
+        for line in BufReader::new(stream).lines() {
+            let line = line.unwrap();
+            if line == "PLAY" {
+                writeln!(writer, "PASS").unwrap();
+            }
+            if line.starts_with("END") {
+                break;
+            }
+        }
+
+        client.join().unwrap();
+        let standings = server.join().unwrap();
+        // With the 2 points of the tiles left to Bob
+        assert_eq!((standings[0].name.as_str(), standings[0].score), ("Ann_Lee", 6));
+
+        let alphabet = Alphabet::new(vec!["A".to_string(), "CH".to_string()]);
+        let tiles = [Tile::new(1, 5, false), Tile::new(0, 0, true), Tile::new(0, 1, false)];
+        assert_eq!(exchange_letters(&tiles, &alphabet), "CHA*");
+    }
+}
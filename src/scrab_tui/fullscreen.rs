@@ -0,0 +1,478 @@
+use crate::scrabbleutils::{Board, PlayerTrait, Action, Direction, Hand, Alphabet, Letter, Tile,
+    GameView, Event};
+use crate::scrabbleutils::bonuses::{WordBonus, LetterBonus};
+use super::terminal::{self, Key, Keys, RawMode};
+use rand::seq::SliceRandom;
+use std::io::{stdin, stdout, Write};
+
+/// Column of the screen where the side panel starts
+const PANEL : usize = 52;
+/// Row of the screen where the rack is shown
+const RACK_ROW : usize = 18;
+/// How many moves of the history are shown
+const HISTORY_LINES : usize = 10;
+
+/// Where the keys go
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Focus {
+    Board,
+    Rack,
+}
+
+/// What the player is preparing during its turn
+struct Editor {
+    /// The tiles of the hand, in the order the player likes
+    rack : Vec<Tile>,
+    /// The tiles laid on the board: position, slot of the rack and letter
+    placed : Vec<(u8, u8, usize, Letter)>,
+    /// The slots of the rack to exchange
+    marked : Vec<bool>,
+    cursor : (u8, u8),
+    /// Where the cursor goes after a tile is laid
+    direction : Direction,
+    focus : Focus,
+    /// The selected slot of the rack
+    selected : usize,
+    /// Whether the selected tile is being moved along the rack
+    holding : bool,
+    /// Told to the player on the next frame
+    message : String,
+}
+
+impl Editor {
+    fn new(rack : Vec<Tile>) -> Editor {
+        Editor {
+            marked : vec![false; rack.len()],
+            rack,
+            placed : Vec::new(),
+            cursor : (7, 7),
+            direction : Direction::Horizontal,
+            focus : Focus::Board,
+            selected : 0,
+            holding : false,
+            message : String::new(),
+        }
+    }
+
+    fn laid(&self, slot : usize) -> bool {
+        self.placed.iter().any(|p| p.2 == slot)
+    }
+
+    /// Get the tile laid at (`x`, `y`) this turn
+    fn placed_at(&self, x : u8, y : u8) -> Option<Tile> {
+        let (_, _, slot, letter) = self.placed.iter().find(|p| p.0 == x && p.1 == y)?;
+        let mut tile = self.rack[*slot].clone();
+        tile.set_wildcard(*letter);
+        Some(tile)
+    }
+
+    /// Move the cursor one spot along its direction, None at the edge
+    fn advance(&self) -> Option<(u8, u8)> {
+        let (x, y) = self.cursor;
+        match self.direction {
+            Direction::Horizontal if x < 14 => Some((x + 1, y)),
+            Direction::Vertical if y < 14 => Some((x, y + 1)),
+            _ => None,
+        }
+    }
+
+    /// Put the cursor on the next spot where a tile can be laid
+    fn skip_taken(&mut self, board : &Board) {
+        while board.get_letter(self.cursor.0, self.cursor.1).is_some()
+                || self.placed_at(self.cursor.0, self.cursor.1).is_some() {
+            match self.advance() {
+                Some(cursor) => self.cursor = cursor,
+                None => return,
+            }
+        }
+    }
+
+    /// Lay the tile for `c` at the cursor, a wildcard if the rack has no such letter
+    fn lay(&mut self, c : char, board : &Board, alphabet : &Alphabet) {
+        let letter = match alphabet.letter(&c.to_string()) {
+            Some(letter) => letter,
+            None => {
+                self.message = format!("{} is not a letter of the game", c);
+                return;
+            }
+        };
+        self.skip_taken(board);
+        let (x, y) = self.cursor;
+        if board.get_letter(x, y).is_some() || self.placed_at(x, y).is_some() {
+            self.message = "There is no room left".to_string();
+            return;
+        }
+        let free = |wildcard : bool| (0..self.rack.len()).find(|slot| !self.laid(*slot)
+            && self.rack[*slot].wildcard() == wildcard
+            && (wildcard || self.rack[*slot].letter() == letter));
+        let slot = match free(false).or_else(|| free(true)) {
+            Some(slot) => slot,
+            None => {
+                self.message = format!("You don't have any {}", alphabet.label(letter));
+                return;
+            }
+        };
+        self.placed.push((x, y, slot, letter));
+        self.marked[slot] = false;
+        if let Some(cursor) = self.advance() {
+            self.cursor = cursor;
+        }
+        self.skip_taken(board);
+    }
+
+    /// Swap two slots of the rack, with what refers to them
+    fn swap(&mut self, a : usize, b : usize) {
+        self.rack.swap(a, b);
+        self.marked.swap(a, b);
+        for placed in self.placed.iter_mut() {
+            if placed.2 == a {
+                placed.2 = b;
+            }
+            else if placed.2 == b {
+                placed.2 = a;
+            }
+        }
+    }
+
+    /// Handle a key
+    ///
+    /// # Return Value
+    /// What the player decided, None while it is still preparing it.
+    fn key(&mut self, key : Key, board : &Board, alphabet : &Alphabet) -> Option<Action> {
+        self.message.clear();
+        match (self.focus, key) {
+            (_, Key::Ctrl('p')) => return Some(Action::Pass),
+            (_, Key::Tab) => {
+                self.holding = false;
+                self.focus = if self.focus == Focus::Board { Focus::Rack } else { Focus::Board };
+            }
+
+            (Focus::Board, Key::Up) => self.cursor.1 = self.cursor.1.saturating_sub(1),
+            (Focus::Board, Key::Down) => self.cursor.1 = (self.cursor.1 + 1).min(14),
+            (Focus::Board, Key::Left) => self.cursor.0 = self.cursor.0.saturating_sub(1),
+            (Focus::Board, Key::Right) => self.cursor.0 = (self.cursor.0 + 1).min(14),
+            (Focus::Board, Key::Char(' ')) => {
+                self.direction = match self.direction {
+                    Direction::Horizontal => Direction::Vertical,
+                    Direction::Vertical => Direction::Horizontal,
+                };
+            }
+            (Focus::Board, Key::Char(c)) => self.lay(c, board, alphabet),
+            (Focus::Board, Key::Backspace) => {
+                if let Some((x, y, _, _)) = self.placed.pop() {
+                    self.cursor = (x, y);
+                }
+            }
+            (Focus::Board, Key::Escape) => self.placed.clear(),
+            (Focus::Board, Key::Enter) => {
+                let letters : Vec<(u8, u8, Letter)> = self.placed.iter()
+                    .map(|p| (p.0, p.1, p.3))
+                    .collect();
+                if letters.is_empty() {
+                    self.message = "Lay some tiles first".to_string();
+                }
+                else {
+                    match board.placed_move(&letters) {
+                        Some(mv) => return Some(Action::Place(mv)),
+                        None => self.message = "The tiles should make one line without holes".to_string(),
+                    }
+                }
+            }
+
+            (Focus::Rack, Key::Left) | (Focus::Rack, Key::Right) if !self.rack.is_empty() => {
+                let next = if key == Key::Left {
+                    self.selected.saturating_sub(1)
+                }
+                else {
+                    (self.selected + 1).min(self.rack.len() - 1)
+                };
+                if self.holding {
+                    self.swap(self.selected, next);
+                }
+                self.selected = next;
+            }
+            (Focus::Rack, Key::Char(' ')) => self.holding = !self.holding && !self.rack.is_empty(),
+            (Focus::Rack, Key::Char('x')) if self.selected < self.rack.len()
+                    && !self.laid(self.selected) => {
+                self.marked[self.selected] = !self.marked[self.selected];
+            }
+            (Focus::Rack, Key::Char('s')) => {
+                self.placed.clear();
+                self.holding = false;
+                self.marked = vec![false; self.rack.len()];
+                self.rack.shuffle(&mut rand::thread_rng());
+            }
+            (Focus::Rack, Key::Enter) => {
+                let tiles : Vec<Tile> = self.rack.iter().zip(self.marked.iter())
+                    .filter(|(_, marked)| **marked)
+                    .map(|(tile, _)| tile.clone())
+                    .collect();
+                if tiles.is_empty() {
+                    self.message = "Mark the tiles to exchange with x first".to_string();
+                }
+                else {
+                    return Some(Action::Exchange(tiles));
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// Write what a player did, None for what is not a move
+fn history_line(event : &Event, game : &GameView, alphabet : &Alphabet) -> Option<String> {
+    let line = match event {
+        Event::Placed { player, mv, breakdown } => format!("{} {} {}", game.name(*player),
+            mv.notation(alphabet), breakdown.total()),
+        Event::Exchanged { player, count } => format!("{} exchanged {}", game.name(*player), count),
+        Event::Passed { player } => format!("{} passed", game.name(*player)),
+        _ => return None,
+    };
+    Some(line)
+}
+
+/// Get the spots of the last move added to the board
+fn last_move(game : &GameView) -> Vec<(u8, u8)> {
+    let mv = game.history().iter().rev().find_map(|event| match event {
+        Event::Placed { mv, .. } => Some(mv),
+        _ => None,
+    });
+    match mv {
+        None => Vec::new(),
+        Some(mv) => (0..mv.word().len() as u8).map(|i| match mv.direction() {
+            Direction::Horizontal => (mv.x() + i, mv.y()),
+            Direction::Vertical => (mv.x(), mv.y() + i),
+        }).collect(),
+    }
+}
+
+/// Write a whole frame of the screen
+fn render(editor : &Editor, game : &GameView, alphabet : &Alphabet) -> String {
+    let board = game.board();
+    let label = |tile : &Tile| {
+        let label = alphabet.label(tile.letter());
+        if tile.wildcard() { label.to_lowercase() } else { label.to_string() }
+    };
+    let mut frame = String::from(terminal::CLEAR);
+    frame.push_str(terminal::HIDE_CURSOR);
+
+    // The board, columns are letters and rows are numbers like in the notation
+    frame.push_str("   ");
+    for x in 0..15u8 {
+        frame.push_str(&format!(" {} ", (b'A' + x) as char));
+    }
+    let last = last_move(game);
+    for y in 0..15u8 {
+        frame.push_str(&terminal::goto(y as usize + 2, 1));
+        frame.push_str(&format!("{:>2} ", y + 1));
+        for x in 0..15u8 {
+            let (text, colors) = match (editor.placed_at(x, y), board.get_tile(x, y)) {
+                (Some(tile), _) => (label(&tile), "1;30;42"),
+                (None, Some(tile)) if last.contains(&(x, y)) => (label(&tile), "30;43"),
+                (None, Some(tile)) => (label(&tile), "30;47"),
+                (None, None) => match board.get_bonuses(x, y) {
+                    (_, WordBonus::Triple) => ("W3".to_string(), "37;41"),
+                    (_, WordBonus::Double) => ("W2".to_string(), "37;45"),
+                    (LetterBonus::Triple, _) => ("L3".to_string(), "37;44"),
+                    (LetterBonus::Double, _) => ("L2".to_string(), "30;46"),
+                    _ => (".".to_string(), "90"),
+                },
+            };
+            frame.push_str(&terminal::color(colors));
+            if editor.focus == Focus::Board && editor.cursor == (x, y) {
+                frame.push_str(terminal::REVERSE);
+            }
+            frame.push_str(&format!("{:^3}", text));
+            frame.push_str(terminal::RESET);
+        }
+    }
+
+    // The side panel: scores, bag and history
+    let mut panel : Vec<String> = vec![format!("{}Round {}{}", terminal::BOLD, game.round(), terminal::RESET)];
+    for player in 0..game.player_count() {
+        let marker = if player == game.me() { ">" } else { " " };
+        panel.push(format!("{} {:<12} {:>4}  {}", marker, game.name(player), game.score(player),
+            game.clock(player)));
+    }
+    panel.push(format!("{} tiles in the bag", game.bag_len()));
+    panel.push(String::new());
+    panel.push(format!("{}History{}", terminal::BOLD, terminal::RESET));
+    let history : Vec<String> = game.history().iter()
+        .filter_map(|event| history_line(event, game, alphabet))
+        .collect();
+    panel.extend(history.iter().skip(history.len().saturating_sub(HISTORY_LINES)).cloned());
+    for (row, line) in panel.iter().enumerate() {
+        frame.push_str(&terminal::goto(row + 2, PANEL));
+        frame.push_str(line);
+    }
+
+    // The rack
+    frame.push_str(&terminal::goto(RACK_ROW, 1));
+    frame.push_str("Rack ");
+    for (slot, tile) in editor.rack.iter().enumerate() {
+        let colors = if editor.laid(slot) {
+            "90"
+        }
+        else if editor.marked[slot] {
+            "37;41"
+        }
+        else if editor.holding && slot == editor.selected {
+            "30;43"
+        }
+        else {
+            "30;47"
+        };
+        frame.push_str(&terminal::color(colors));
+        if editor.focus == Focus::Rack && slot == editor.selected {
+            frame.push_str(terminal::REVERSE);
+        }
+        let text = if tile.wildcard() { "*".to_string() } else { label(tile) };
+        frame.push_str(&format!(" {}{:<2}", text, tile.points()));
+        frame.push_str(terminal::RESET);
+        frame.push(' ');
+    }
+    let arrow = match editor.direction {
+        Direction::Horizontal => "across",
+        Direction::Vertical => "down",
+    };
+    frame.push_str(&format!("   laying {}", arrow));
+
+    frame.push_str(&terminal::goto(RACK_ROW + 2, 1));
+    frame.push_str(&terminal::color("1;31"));
+    frame.push_str(&editor.message);
+    frame.push_str(terminal::RESET);
+    frame.push_str(&terminal::goto(RACK_ROW + 4, 1));
+    frame.push_str(match editor.focus {
+        Focus::Board => "arrows: move   letters: lay   space: turn   backspace: take back   \
+            esc: take all back   enter: play   tab: rack   ctrl-p: pass",
+        Focus::Rack => "arrows: select   space: pick up/drop   x: mark   s: shuffle   \
+            enter: exchange the marked tiles   tab: board   ctrl-p: pass",
+    });
+    frame
+}
+
+/// A player using the whole terminal
+///
+/// The board, the rack, the scores and the history are drawn with terminal
+/// control sequences. Tiles are laid directly on the board with the keys.
+/// It asks questions line by line if the standard input is not a terminal.
+pub struct FullScreenPlayer {
+    name : String,
+    /// The letters of the game, to read and write words
+    alphabet : Alphabet,
+    /// The round and the length of the history when a move was last given,
+    /// to know it was refused if nothing happened since
+    last_turn : Option<(u32, usize)>,
+}
+
+impl FullScreenPlayer {
+    pub fn new(name : String, alphabet : Alphabet) -> FullScreenPlayer {
+        FullScreenPlayer {
+            name,
+            alphabet,
+            last_turn : None,
+        }
+    }
+}
+
+impl PlayerTrait for FullScreenPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn play(&mut self, game : &GameView, hand : &Hand) -> Action {
+        let raw = match RawMode::enable() {
+            Ok(raw) => raw,
+            Err(_) => return super::ask_action(game, hand, &self.alphabet),
+        };
+        let mut editor = Editor::new(hand.get());
+        let turn = (game.round(), game.history().len());
+        if self.last_turn == Some(turn) {
+            editor.message = "Your move was refused".to_string();
+        }
+        let stdin = stdin();
+        let mut keys = Keys::new(stdin.lock());
+        let mut out = stdout();
+        let action = loop {
+            let _ = write!(out, "{}", render(&editor, game, &self.alphabet));
+            let _ = out.flush();
+            let key = match keys.next_key() {
+                Some(key) => key,
+                None => break Action::Pass,
+            };
+            if key == Key::Ctrl('c') {
+                drop(raw);
+                print!("{}{}{}", terminal::RESET, terminal::SHOW_CURSOR, terminal::CLEAR);
+                std::process::exit(130);
+            }
+            match editor.key(key, game.board(), &self.alphabet) {
+                Some(Action::Place(mv)) if !game.dico().exists(mv.word()) => {
+                    editor.message = format!("{} is not in the dictionnary",
+                        self.alphabet.decode(mv.word()));
+                }
+                Some(action) => break action,
+                None => {}
+            }
+        };
+        let _ = write!(out, "{}{}", terminal::SHOW_CURSOR, terminal::CLEAR);
+        let _ = out.flush();
+        self.last_turn = Some(turn);
+        action
+    }
+
+    fn move_score(&mut self, _score : u32) {
+    }
+
+    fn total_score(&mut self, _score : u32) {
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Editor;
+    use crate::scrabbleutils::{Board, TileSet, Tile, Action, Move, Direction};
+    use crate::scrab_tui::terminal::Key;
+
+    #[test]
+    fn editor() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let letter = |label : &str| ab.letter(label).unwrap();
+        let mut board = Board::new();
+        let hello : Vec<Tile> = ab.encode("HELLO").unwrap().into_iter()
+            .map(|l| Tile::new(l, 1, false)).collect();
+        board.add_move(Move::new(5, 7, ab.encode("HELLO").unwrap(), Direction::Horizontal), hello);
+        let rack = vec![Tile::new(letter("B"), 3, false), Tile::new(letter("T"), 1, false),
+            Tile::new(0, 0, true)];
+        let mut editor = Editor::new(rack);
+        let keys = |editor : &mut Editor, keys : &[Key]| {
+            keys.iter().map(|key| editor.key(*key, &board, ab)).last().unwrap()
+        };
+
+        // B above the E, going down jumps over it, then a wildcard for the second T
+        editor.cursor = (6, 6);
+        let action = keys(&mut editor, &[Key::Char(' '), Key::Char('b'), Key::Char('t'),
+            Key::Char('t'), Key::Enter]);
+        match action {
+            Some(Action::Place(mv)) => {
+                assert_eq!((mv.x(), mv.y(), mv.word()), (6, 6, &ab.encode("BETT").unwrap()[..]));
+            }
+            _ => panic!("the tiles should be played"),
+        }
+        assert!(editor.placed_at(6, 9).unwrap().wildcard());
+        assert_eq!(editor.cursor, (6, 10));
+        assert!(keys(&mut editor, &[Key::Char('z')]).is_none());
+        assert_eq!(editor.message, "You don't have any Z");
+
+        // Taking back every tile, then moving the B to the end of the rack
+        keys(&mut editor, &[Key::Escape, Key::Tab, Key::Char(' '), Key::Right, Key::Right,
+            Key::Char(' ')]);
+        assert_eq!(editor.rack[2].letter(), letter("B"));
+        match keys(&mut editor, &[Key::Char('x'), Key::Enter]) {
+            Some(Action::Exchange(tiles)) => assert_eq!(tiles, vec![Tile::new(letter("B"), 3, false)]),
+            _ => panic!("the B should be exchanged"),
+        }
+        assert!(matches!(keys(&mut editor, &[Key::Ctrl('p')]), Some(Action::Pass)));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::scrabbleutils::{PlayerTrait, GameView, Hand, Action, Event, Standing};
+use super::terminal;
+use super::read_line;
+use std::io::{stdout, BufRead, Write};
+
+/// Wait for `name` to be in front of the screen, then clear it
+///
+/// What the last player did stays on the screen until then.
+fn wait_for<R : BufRead>(input : &mut R, name : &str) {
+    print!("\nPress Enter when {} is ready", name);
+    let _ = stdout().flush();
+    // Nothing more to read is as good as ready
+    read_line(input);
+    print!("{}", terminal::CLEAR_ALL);
+}
+
+/// A player sharing the terminal with other players
+///
+/// The player is waited for before its hand is shown, and the screen is
+/// cleared when its move is made, so the others can't see the hand. The
+/// score and the commentary of the move come after, they stay until the next
+/// player is ready.
+pub struct Hotseat<R : BufRead> {
+    player : Box<dyn PlayerTrait>,
+    /// Where the players say they are ready
+    input : R,
+    /// The round and the length of the history after the last turn, nothing
+    /// happened since if the same player is asked again: its move was refused
+    last_turn : Option<(u32, usize)>,
+}
+
+impl<R : BufRead> Hotseat<R> {
+    /// Create a player sharing the terminal
+    ///
+    /// # Arguments
+    /// * `player` - The player in front of the terminal.
+    /// * `input` - Where the player presses Enter when ready, usually the
+    ///   standard input read with `StdinLines` so `player` can read it too.
+    pub fn new(player : Box<dyn PlayerTrait>, input : R) -> Hotseat<R> {
+        Hotseat {
+            player,
+            input,
+            last_turn : None,
+        }
+    }
+}
+
+impl<R : BufRead> PlayerTrait for Hotseat<R> {
+    fn name(&self) -> &str {
+        self.player.name()
+    }
+
+    fn play(&mut self, game : &GameView, hand : &Hand) -> Action {
+        let turn = (game.round(), game.history().len());
+        // The player is still in front of the screen when its move is refused
+        if self.last_turn != Some(turn) {
+            wait_for(&mut self.input, self.player.name());
+        }
+        let action = self.player.play(game, hand);
+        // Hides the hand, the score is told once the move is accepted
+        print!("{}", terminal::CLEAR_ALL);
+        let _ = stdout().flush();
+        self.last_turn = Some(turn);
+        action
+    }
+
+    fn move_score(&mut self, score : u32) {
+        self.player.move_score(score);
+    }
+
+    fn total_score(&mut self, score : u32) {
+        self.player.total_score(score);
+    }
+
+    fn new_game(&mut self, game : &GameView) {
+        self.player.new_game(game);
+    }
+
+    fn opponent_moved(&mut self, game : &GameView, event : &Event) {
+        self.player.opponent_moved(game, event);
+    }
+
+    fn game_over(&mut self, standings : &[Standing]) {
+        self.player.game_over(standings);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Hotseat;
+    use crate::scrabbleutils::{PlayerTrait, Game, GameView, Hand, Action, Dico, TileSet};
+    use std::io::BufRead;
+    use std::sync::Arc;
+
+    struct Passer;
+
+    impl PlayerTrait for Passer {
+        fn name(&self) -> &str {
+            "passer"
+        }
+
+        fn play(&mut self, _game : &GameView, _hand : &Hand) -> Action {
+            Action::Pass
+        }
+
+        fn move_score(&mut self, _score : u32) {
+        }
+
+        fn total_score(&mut self, _score : u32) {
+        }
+    }
+
+    #[test]
+    fn waits_once_per_turn() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let mut game = Game::new(&tileset, Arc::new(Dico::new(tileset.alphabet())));
+        game.add_player(Box::new(Passer));
+        game.add_player(Box::new(Passer));
+        game.set_first(0);
+        game.start();
+        let mut hotseat = Hotseat::new(Box::new(Passer), "\n\n".as_bytes());
+
+        assert!(matches!(hotseat.play(&game.view(0), &Hand::new()), Action::Pass));
+        assert_eq!(hotseat.input, "\n".as_bytes());
+        // A refused move is played again without waiting
+        hotseat.play(&game.view(0), &Hand::new());
+        assert_eq!(hotseat.input, "\n".as_bytes());
+
+        // Both players passed, it is the next turn
+        game.play_turn().unwrap();
+        game.play_turn().unwrap();
+        hotseat.play(&game.view(0), &Hand::new());
+        assert!(hotseat.input.fill_buf().unwrap().is_empty());
+    }
+}
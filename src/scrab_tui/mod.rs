@@ -1,10 +1,33 @@
-use crate::scrabbleutils::{Board, PlayerTrait, Move, Direction::*, Hand};
+use crate::scrabbleutils::{Board, PlayerTrait, Move, Action, Direction::*, Hand, Dico,
+    Alphabet, Letter, Game, GameView, Event, Observer, ScoreBreakdown, Candidate, Tile, parse_square,
+    best_moves, WILDCARD};
+use std::cell::RefCell;
 use crate::scrabbleutils::bonuses::{WordBonus, LetterBonus};
-use std::io::stdin;
+use std::io::{stdin, BufRead};
+
+/// Plays on a game server
+mod client;
+/// Escape sequences and keys of the terminal
+mod terminal;
+/// The interface using the whole terminal
+mod fullscreen;
+/// Players taking turns at the same terminal
+mod hotseat;
+/// Setting up a position by hand
+mod setup;
+/// Word quizzes in the terminal
+mod study;
+
+pub use client::run_client;
+pub use fullscreen::FullScreenPlayer;
+pub use hotseat::Hotseat;
+pub use terminal::StdinLines;
+pub use setup::edit_position;
+pub use study::run_quiz;
 
 // I don't like designing ui, please feel free to improve it.
 
-pub fn print_board(board : &Board) {
+pub fn print_board(board : &Board, alphabet : &Alphabet) {
     print!("     ");
     for _ in 0..15 {
         print!("-----");
@@ -35,7 +58,7 @@ pub fn print_board(board : &Board) {
                 let letter = board.get_letter(x - 1, y);
                 match letter {
                     Some(x) => {
-                        print!(" {}  |", x);
+                        print!(" {:<3}|", alphabet.label(x));
                     }
                     None => {
                         // Check letter and word bonus
@@ -90,7 +113,7 @@ pub fn print_board(board : &Board) {
     println!("");
 }
 
-pub fn print_hand(hand : &Hand) {
+pub fn print_hand(hand : &Hand, alphabet : &Alphabet) {
     // Top line
     for _ in 0..hand.get().len() {
         print!("-----");
@@ -99,7 +122,12 @@ pub fn print_hand(hand : &Hand) {
     print!("-\n|");
     // Second line (letters + separators)
     for i in hand.get() {
-        print!(" {}  |", i.letter());
+        if i.wildcard() {
+            print!(" *  |");
+        }
+        else {
+            print!(" {:<3}|", alphabet.label(i.letter()));
+        }
     }
     // End of second line + start separator of third line
     print!("\n|");
@@ -115,90 +143,387 @@ pub fn print_hand(hand : &Hand) {
     print!("-\n");
 }
 
-pub struct SimplePlayer {
-    name : String,
+/// Print the definitions of the known `words`
+pub fn print_definitions(dico : &Dico, words : &[Vec<Letter>]) {
+    for word in words {
+        let word = dico.alphabet().decode(word);
+        if let Some(definition) = dico.definition(&word) {
+            println!("{} : {}", word, definition);
+        }
+    }
 }
 
-impl SimplePlayer {
-    pub fn new(name : String) -> SimplePlayer {
-        SimplePlayer {
-            name,
+/// Print the definition of a challenged `word`, or that there is none
+pub fn print_challenged(dico : &Dico, word : &str) {
+    match dico.definition(word) {
+        Some(definition) => println!("{} : {}", word, definition),
+        None => println!("{} : no definition", word),
+    }
+}
+
+/// Print the clock of each player, a `>` shows whose turn it is
+pub fn print_clocks(game : &Game) {
+    let clocks : Vec<String> = game.players().iter().zip(game.clocks()).enumerate()
+        .map(|(i, (player_data, clock))| {
+            let marker = if i == game.current() { ">" } else { " " };
+            format!("{}{} {}", marker, player_data.name, clock)
+        })
+        .collect();
+    println!("{}", clocks.join(" | "));
+}
+
+/// Write the score of each player and the tiles left in the bag
+pub fn scores_line(names : &[String], scores : &[u32], bag : usize) -> String {
+    let scores : Vec<String> = names.iter().zip(scores)
+        .map(|(name, score)| format!("{} : {}", name, score))
+        .collect();
+    format!("{} | {} tiles in the bag", scores.join(" | "), bag)
+}
+
+/// Prints what the players do
+pub struct Commentator {
+    /// The letters of the game, to write the words
+    alphabet : Alphabet,
+    /// The names of the players, known once the game starts
+    names : RefCell<Vec<String>>,
+}
+
+impl Commentator {
+    pub fn new(alphabet : Alphabet) -> Commentator {
+        Commentator {
+            alphabet,
+            names : RefCell::new(Vec::new()),
         }
     }
+
+    fn name(&self, player : usize) -> String {
+        self.names.borrow().get(player).cloned().unwrap_or_default()
+    }
 }
 
-impl PlayerTrait for SimplePlayer {
-    fn name(&self) -> &str {
-        return &self.name;
+impl Observer for Commentator {
+    fn notify(&self, event : &Event) {
+        match event {
+            Event::GameStart { players } => {
+                *self.names.borrow_mut() = players.clone();
+            }
+            Event::Placed { player, breakdown, .. } => {
+                let mut details : Vec<String> = breakdown.words.iter()
+                    .map(|(word, score)| format!("{} {}", self.alphabet.decode(word), score))
+                    .collect();
+                if breakdown.bingo > 0 {
+                    details.push(format!("bingo {}", breakdown.bingo));
+                }
+                println!("{} made {} points: {}", self.name(*player), breakdown.total(),
+                    details.join(", "));
+            }
+            Event::Exchanged { player, count } => {
+                println!("{} exchanged {} tiles", self.name(*player), count);
+            }
+            Event::Passed { player } => {
+                println!("{} passed", self.name(*player));
+            }
+            Event::GameEnd { standings } => {
+                for (rank, standing) in standings.iter().enumerate() {
+                    print!("{}. {} : {} points", rank + 1, standing.name, standing.score);
+                    if standing.tiles > 0 {
+                        print!(" ({} won for going out)", standing.tiles);
+                    }
+                    else if standing.tiles < 0 {
+                        print!(" ({} lost for the tiles left)", -standing.tiles);
+                    }
+                    if standing.penalty > 0 {
+                        print!(" ({} lost for going over time)", standing.penalty);
+                    }
+                    println!();
+                }
+            }
+            _ => {}
+        }
     }
+}
 
-    fn play(&self, board : &Board, hand : &Hand) -> Move {
-        let mv : Move;
-        let mut error_msg : Option<&str> = None;
-        loop {
-            print_board(board);
-            print_hand(hand);
-            if let Some(msg) = error_msg {
-                eprintln!("{}", msg);
-            }
-            let word;
-            println!("What do you want to play ?");
-            let mut line = String::new();
-            stdin().read_line(&mut line).expect("This is not a correct string");
-            let words : Vec<&str> = line.split_whitespace().collect();
-            if words.len() != 1 {
-                error_msg = Some("You should give exactly one word");
+/// Read a line, None if there is nothing more to read
+fn read_line<R : BufRead>(input : &mut R) -> Option<String> {
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
+}
+
+/// The position and the letter of each tile added to the board
+type Placement = Vec<(u8, u8, Letter)>;
+
+/// Read tiles added one by one, like `H8 Q, H9 I, H10 T`
+///
+/// # Return Value
+/// None if the line is not written this way, otherwise the position and the
+/// letter of each tile or what is wrong with them.
+fn read_tiles(line : &str, alphabet : &Alphabet) -> Option<Result<Placement, String>> {
+    let first = line.split_whitespace().next()?;
+    parse_square(first.trim_end_matches(','))?;
+    let mut tiles : Placement = Vec::new();
+    for tile in line.split(',').filter(|t| !t.trim().is_empty()) {
+        let parts : Vec<&str> = tile.split_whitespace().collect();
+        let (x, y) = match parts.as_slice() {
+            [square, _] => match parse_square(square) {
+                Some(position) => position,
+                None => return Some(Err(format!("`{}` is not a spot like H8", square))),
+            },
+            _ => return Some(Err(format!("`{}` should be a spot and a letter, like H8 Q", tile.trim()))),
+        };
+        match alphabet.letter(parts[1]) {
+            Some(letter) => tiles.push((x, y, letter)),
+            None => return Some(Err(format!("{} is not a letter of the game", parts[1]))),
+        }
+    }
+    Some(Ok(tiles))
+}
+
+/// Show the words a move makes and their score, then ask to play it
+///
+/// # Return Value
+/// Whether the player wants it, None if `input` has nothing more to read.
+fn confirm<R : BufRead>(input : &mut R, breakdown : &ScoreBreakdown, alphabet : &Alphabet) -> Option<bool> {
+    let mut details : Vec<String> = breakdown.words.iter()
+        .map(|(word, score)| format!("{} {}", alphabet.decode(word), score))
+        .collect();
+    if breakdown.bingo > 0 {
+        details.push(format!("bingo {}", breakdown.bingo));
+    }
+    println!("{} : {} points", details.join(", "), breakdown.total());
+    println!("Play it ? (y/n)");
+    let line = read_line(input)?;
+    Some(line.trim().eq_ignore_ascii_case("y") || line.trim().eq_ignore_ascii_case("yes"))
+}
+
+/// How many moves a hint shows when no number is given
+const HINT_COUNT : usize = 5;
+
+/// Read a hint request, `?` or `? 10`
+///
+/// # Return Value
+/// How many moves are asked, None if the line is not a hint request.
+fn read_hint(line : &str) -> Option<usize> {
+    let count = line.trim().strip_prefix('?')?.trim();
+    if count.is_empty() {
+        return Some(HINT_COUNT);
+    }
+    count.parse().ok().filter(|count| *count > 0)
+}
+
+/// Read a pass, `-` or `pass`, or an exchange, `-` then the letters like `-QXZ`
+///
+/// `*` stands for a wildcard in the letters to exchange.
+///
+/// # Return Value
+/// None if the line is neither, otherwise the action or why the tiles
+/// can't be exchanged.
+fn read_pass_or_exchange(line : &str, alphabet : &Alphabet, hand : &Hand) -> Option<Result<Action, String>> {
+    let line = line.trim();
+    if line == "-" || line.eq_ignore_ascii_case("pass") {
+        return Some(Ok(Action::Pass));
+    }
+    let letters = line.strip_prefix('-')?;
+    let wildcards = letters.chars().filter(|c| *c == WILDCARD).count();
+    let letters : String = letters.chars().filter(|c| *c != WILDCARD).collect();
+    let mut tiles : Vec<Tile> = match alphabet.encode(&letters) {
+        Some(letters) => letters.into_iter().map(|letter| Tile::new(letter, 0, false)).collect(),
+        None => return Some(Err("These letters are not in the game".to_string())),
+    };
+    tiles.extend((0..wildcards).map(|_| Tile::new(0, 0, true)));
+    match Hand::from_tiles(hand.get()).remove_tiles(&tiles) {
+        Some(given) => Some(Ok(Action::Exchange(given))),
+        None => Some(Err("You can only exchange tiles of your hand".to_string())),
+    }
+}
+
+/// Show the moves of a hint, then ask which one to play
+///
+/// # Return Value
+/// The move picked, None if the player goes back to the question, or if
+/// `input` has nothing more to read.
+fn pick_hint<R : BufRead>(input : &mut R, hints : &[Candidate], alphabet : &Alphabet) -> Option<Move> {
+    for (rank, candidate) in hints.iter().enumerate() {
+        println!("{}. {} : {} points", rank + 1, candidate.mv().notation(alphabet), candidate.score());
+    }
+    println!("Type the number of a move to play it, or Enter to go back");
+    let line = read_line(input)?;
+    let rank : usize = line.trim().parse().ok()?;
+    hints.get(rank.checked_sub(1)?).map(|candidate| candidate.mv().clone())
+}
+
+/// Ask for a move until it is well written
+///
+/// The board, the `status` line and the hand are printed before each question.
+/// The move is given either as a whole word then its position and direction,
+/// or as the tiles added to the board, like `H8 Q, H9 I, H10 T`. Those are
+/// shown with `preview` and played once the player agrees. Typing `?`
+/// lists the best moves, any of them can be played right away. `-` passes
+/// and `-` followed by letters exchanges them.
+///
+/// # Arguments
+/// * `preview` - Scores a move, or tells why it can't be played.
+/// * `hints` - Finds the given number of best moves, None if the player
+///   can't get hints.
+///
+/// # Return Value
+/// What the player does, or None if `input` has nothing more to read.
+pub fn ask_move<R : BufRead>(input : &mut R, board : &Board, hand : &Hand, alphabet : &Alphabet,
+        status : &str, preview : &dyn Fn(&Move) -> Result<ScoreBreakdown, String>,
+        hints : Option<&dyn Fn(usize) -> Vec<Candidate>>) -> Option<Action> {
+    let mv : Move;
+    let mut error_msg : Option<String> = None;
+    loop {
+        print_board(board, alphabet);
+        println!("{}", status);
+        print_hand(hand, alphabet);
+        if let Some(msg) = error_msg.take() {
+            eprintln!("{}", msg);
+        }
+        let word;
+        println!("What do you want to play ?\n\tex : HELLO, or only the tiles you add : H8 Q, H9 I, H10 T");
+        println!("\t- passes, -QX exchanges Q and X, * for a blank");
+        if hints.is_some() {
+            println!("\t? shows the best moves, ? 10 the ten best");
+        }
+        let line = read_line(input)?;
+        if let Some(count) = read_hint(&line) {
+            match hints.map(|hints| hints(count)) {
+                None => error_msg = Some("Hints are not available in this game".to_string()),
+                Some(moves) if moves.is_empty() => error_msg = Some("No move can be made".to_string()),
+                Some(moves) => if let Some(mv) = pick_hint(input, &moves, alphabet) {
+                    return Some(Action::Place(mv));
+                },
+            }
+            continue;
+        }
+        match read_pass_or_exchange(&line, alphabet, hand) {
+            None => {}
+            Some(Ok(action)) => return Some(action),
+            Some(Err(e)) => {
+                error_msg = Some(e);
                 continue;
             }
-            else {
-                word = words[0].to_owned();
-            }
-            println!("At what position do you want to play it ?\n\tex : 1 15");
-            let mut line = String::new();
-            stdin().read_line(&mut line).expect("This is not a correct string");
-            let positions : Vec<&str> = line.split_whitespace().collect();
-            if positions.len() != 2 {
-                error_msg = Some("You should give two positions");
+        }
+        match read_tiles(&line, alphabet) {
+            None => {}
+            Some(Err(e)) => {
+                error_msg = Some(e);
                 continue;
             }
-            let positions : Vec<Result<u8, std::num::ParseIntError>> = positions.iter().map(|e| e.parse::<u8>()).collect();
-            if positions.iter().any(|e| e.is_err()) {
-                error_msg = Some("Positions should be numbers, the first is on the x coordinate from 1 to 15, the second in the descending y coordinate from 1 to 15.");
+            Some(Ok(tiles)) => {
+                let placed = match board.placed_move(&tiles) {
+                    Some(placed) => placed,
+                    None => {
+                        error_msg = Some("The tiles should be on free spots, in one line without holes".to_string());
+                        continue;
+                    }
+                };
+                match preview(&placed) {
+                    Ok(breakdown) => {
+                        if confirm(input, &breakdown, alphabet)? {
+                            return Some(Action::Place(placed));
+                        }
+                    }
+                    Err(e) => error_msg = Some(e),
+                }
                 continue;
             }
-            let positions : Vec<u8> = positions.into_iter().map(|e| e.unwrap().clone().to_owned()).collect();
-            if positions.iter().any(|e| e < &1 || e > &15) {
-                error_msg = Some("Positions should be between 1 and 15");
+        }
+        let words : Vec<&str> = line.split_whitespace().collect();
+        if words.len() != 1 {
+            error_msg = Some("You should give exactly one word".to_string());
+            continue;
+        }
+        match alphabet.encode(words[0]) {
+            Some(letters) => word = letters,
+            None => {
+                error_msg = Some("This word has letters that are not in the game".to_string());
                 continue;
             }
-            println!("Choose your direction (H/V) :");
-            let mut line = String::new();
-            stdin().read_line(&mut line).expect("This is not a correct string");
-            let line : Vec<&str> = line.split_whitespace().collect();
-            if line.len() == 0 {
-                error_msg = Some("Please provide at least the first character of a direction");
+        }
+        println!("At what position do you want to play it ?\n\tex : 1 15");
+        let line = read_line(input)?;
+        let positions : Vec<&str> = line.split_whitespace().collect();
+        if positions.len() != 2 {
+            error_msg = Some("You should give two positions".to_string());
+            continue;
+        }
+        let positions : Vec<Result<u8, std::num::ParseIntError>> = positions.iter().map(|e| e.parse::<u8>()).collect();
+        if positions.iter().any(|e| e.is_err()) {
+            error_msg = Some("Positions should be numbers, the first is on the x coordinate from 1 to 15, the second in the descending y coordinate from 1 to 15.".to_string());
+            continue;
+        }
+        let positions : Vec<u8> = positions.into_iter().map(|e| e.unwrap().clone().to_owned()).collect();
+        if positions.iter().any(|e| e < &1 || e > &15) {
+            error_msg = Some("Positions should be between 1 and 15".to_string());
+            continue;
+        }
+        println!("Choose your direction (H/V) :");
+        let line = read_line(input)?;
+        let line : Vec<&str> = line.split_whitespace().collect();
+        if line.len() == 0 {
+            error_msg = Some("Please provide at least the first character of a direction".to_string());
+            continue;
+        }
+        let direction_char = line.get(0).unwrap().chars().next().unwrap();
+        let direction = match direction_char {
+            'H' | 'h' => Horizontal,
+            'V' | 'v' => Vertical,
+            _ => {
+                error_msg = Some("Could not get the direction properly, write `V` for vertical and `H` for horizontal".to_string());
                 continue;
             }
-            let direction_char = line.get(0).unwrap().chars().next().unwrap();
-            let direction = match direction_char {
-                'H' | 'h' => Horizontal,
-                'V' | 'v' => Vertical,
-                _ => {
-                    error_msg = Some("Could not get the direction properly, write `V` for vertical and `H` for horizontal");
-                    continue;
-                }
-            };
-            mv = Move::new(positions[0] - 1, positions[1] - 1, word, direction);
-            break;
+        };
+        mv = Move::new(positions[0] - 1, positions[1] - 1, word, direction);
+        break;
+    }
+    Some(Action::Place(mv))
+}
+
+/// Ask the player on the standard input what to play
+fn ask_action(game : &GameView, hand : &Hand, alphabet : &Alphabet) -> Action {
+    let names : Vec<String> = (0..game.player_count()).map(|p| game.name(p).to_string()).collect();
+    let scores : Vec<u32> = (0..game.player_count()).map(|p| game.score(p)).collect();
+    let status = scores_line(&names, &scores, game.bag_len());
+    let preview = |mv : &Move| game.preview(mv).map_err(|e| e.to_string());
+    let hints = |count : usize| best_moves(game.board(), hand, game.dico(), count);
+    // Nothing more can be read
+    ask_move(&mut stdin().lock(), game.board(), hand, alphabet, &status, &preview, Some(&hints))
+        .unwrap_or(Action::Pass)
+}
+
+pub struct SimplePlayer {
+    name : String,
+    /// The letters of the game, to read and write words
+    alphabet : Alphabet,
+}
+
+impl SimplePlayer {
+    pub fn new(name : String, alphabet : Alphabet) -> SimplePlayer {
+        SimplePlayer {
+            name,
+            alphabet,
         }
-        return mv;
+    }
+}
+
+impl PlayerTrait for SimplePlayer {
+    fn name(&self) -> &str {
+        return &self.name;
+    }
+
+    fn play(&mut self, game : &GameView, hand : &Hand) -> Action {
+        ask_action(game, hand, &self.alphabet)
     }
 
-    fn move_score(&self, score : u32) {
+    fn move_score(&mut self, score : u32) {
         println!("Your move made {} points!", score);
     }
 
-    fn total_score(&self, score : u32) {
+    fn total_score(&mut self, score : u32) {
         println!("You have a total of {} points!", score);
     }
 }
@@ -206,3 +531,101 @@ impl PlayerTrait for SimplePlayer {
 pub fn handle_error(error : &str) {
     eprintln!("Error : {}", error);
 }
+
+#[cfg(test)]
+mod test {
+    use super::{ask_move, read_tiles, read_hint, HINT_COUNT};
+    use crate::scrabbleutils::{Board, Hand, Tile, TileSet, Move, Direction, ScoreBreakdown, Dico,
+        Action, best_moves};
+    use std::cell::Cell;
+
+    /// Get the move placed by an action
+    fn placed(action : Option<Action>) -> Move {
+        match action {
+            Some(Action::Place(mv)) => mv,
+            _ => panic!("no move placed"),
+        }
+    }
+
+    #[test]
+    fn tile_input() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let a = ab.letter("A").unwrap();
+        assert_eq!(read_tiles("H8 Q, 9H i", ab), Some(Ok(vec![(7, 7, ab.letter("Q").unwrap()),
+            (7, 8, ab.letter("I").unwrap())])));
+        assert!(matches!(read_tiles("H8 Q, H9", ab), Some(Err(_))));
+        assert_eq!(read_tiles("HELLO", ab), None);
+        // Accented words are words, not tiles
+        assert_eq!(read_tiles("ÉTÉ", ab), None);
+        assert_eq!(read_tiles("ÑU", ab), None);
+        assert_eq!(read_tiles("Ç8 A", ab), None);
+        assert!(matches!(read_tiles("H8 Q, É8 A", ab), Some(Err(_))));
+
+        let board = Board::new();
+        let hand = Hand::from_tiles(vec![Tile::new(a, 1, false); 7]);
+        let previews = Cell::new(0);
+        let preview = |mv : &Move| {
+            previews.set(previews.get() + 1);
+            Ok(ScoreBreakdown { words : vec![(mv.word().to_vec(), 4)], bingo : 0 })
+        };
+        // Refused once, then a spot is missing, then played
+        let mut input = "H8 A, H9 A\nn\nH8 A, H9\nH9 A,H8 A\ny\n".as_bytes();
+        let mv = placed(ask_move(&mut input, &board, &hand, ab, "", &preview, None));
+        assert_eq!((mv.x(), mv.y(), mv.word()), (7, 7, &[a, a][..]));
+        assert!(matches!(mv.direction(), Direction::Vertical));
+        assert_eq!(previews.get(), 2);
+    }
+
+    #[test]
+    fn hints() {
+        assert_eq!(read_hint(" ? "), Some(HINT_COUNT));
+        assert_eq!(read_hint("? 10"), Some(10));
+        assert_eq!(read_hint("? 0"), None);
+        assert_eq!(read_hint("HELLO"), None);
+
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let dico = Dico::from_file("dico.txt", ab).unwrap();
+        let board = Board::new();
+        let hand = Hand::from_tiles(ab.encode("HELLOIT").unwrap().into_iter()
+            .map(|l| Tile::new(l, tileset.get_points(l), false)).collect());
+        let preview = |_ : &Move| Err("not previewed".to_string());
+        let hints = |count : usize| best_moves(&board, &hand, &dico, count);
+        let best = best_moves(&board, &hand, &dico, 2);
+        // Going back once, then picking the second move
+        let mut input = "?\n\n? 2\n2\n".as_bytes();
+        let mv = placed(ask_move(&mut input, &board, &hand, ab, "", &preview, Some(&hints)));
+        assert_eq!(mv.notation(ab), best[1].mv().notation(ab));
+
+        let mut input = "?\n".as_bytes();
+        assert!(ask_move(&mut input, &board, &hand, ab, "", &preview, None).is_none());
+    }
+
+    #[test]
+    fn pass_and_exchange() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let board = Board::new();
+        let mut tiles : Vec<Tile> = ab.encode("QXA").unwrap().into_iter()
+            .map(|l| Tile::new(l, tileset.get_points(l), false)).collect();
+        tiles.push(Tile::new(0, 0, true));
+        let hand = Hand::from_tiles(tiles);
+        let preview = |_ : &Move| Err("not previewed".to_string());
+
+        let mut input = "-\n".as_bytes();
+        assert!(matches!(ask_move(&mut input, &board, &hand, ab, "", &preview, None), Some(Action::Pass)));
+        let mut input = " Pass \n".as_bytes();
+        assert!(matches!(ask_move(&mut input, &board, &hand, ab, "", &preview, None), Some(Action::Pass)));
+        // Tiles not in the hand are refused, then Q, X and the blank go
+        let mut input = "-ZZ\n-qx*\n".as_bytes();
+        match ask_move(&mut input, &board, &hand, ab, "", &preview, None) {
+            Some(Action::Exchange(given)) => {
+                assert_eq!(given.len(), 3);
+                assert_eq!(given.iter().filter(|t| t.wildcard()).count(), 1);
+                assert!(given.iter().all(|t| t.wildcard() || t.letter() != ab.letter("A").unwrap()));
+            }
+            _ => panic!("no exchange"),
+        }
+    }
+}
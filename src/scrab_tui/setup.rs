@@ -0,0 +1,189 @@
+use crate::scrabbleutils::{Position, TileSet, Tile, Dico, Hand, parse_square, best_moves};
+use super::{print_board, print_hand, read_line, handle_error};
+use std::io::BufRead;
+
+/// Stands for a blank in a rack or in the bag
+const BLANK : char = '?';
+/// Takes a tile off the board
+const EMPTY : char = '.';
+/// How many moves `best` shows when no number is given
+const BEST_COUNT : usize = 5;
+
+const HELP : &str = "\
+  8H HELLO      puts tiles from H8 to the right, H8 HELLO downwards, lowercase letters are blanks
+  H8 .          takes the tile off H8, H8 ... the three tiles downwards
+  rack 1 AE?    gives a rack to the first player, ? is a blank, rack 1 - empties it
+  bag EEIO?     gives what is in the bag, bag unseen for the tiles not seen elsewhere
+  best 1 [n]    shows the best moves of the first player
+  done          plays from this position";
+
+/// Read letters, uppercase for a tile, lowercase for a blank standing for
+/// the letter and `?` for a blank standing for nothing yet
+///
+/// # Return Value
+/// None if a letter is not in the game.
+fn read_letters(letters : &str, tileset : &TileSet) -> Option<Vec<Tile>> {
+    let alphabet = tileset.alphabet();
+    let mut tiles : Vec<Tile> = Vec::with_capacity(letters.len());
+    let mut rest = letters;
+    while let Some(first) = rest.chars().next() {
+        if first == BLANK {
+            tiles.push(Tile::new(0, 0, true));
+            rest = &rest[first.len_utf8()..];
+            continue;
+        }
+        // The letters of the same case are read together, a letter can be
+        // written with several characters
+        let end = rest.find(|c : char| c == BLANK || c.is_lowercase() != first.is_lowercase())
+            .unwrap_or(rest.len());
+        for letter in alphabet.encode(&rest[..end])? {
+            if first.is_lowercase() {
+                let mut blank = Tile::new(0, 0, true);
+                blank.set_wildcard(letter);
+                tiles.push(blank);
+            }
+            else {
+                tiles.push(Tile::new(letter, tileset.get_points(letter), false));
+            }
+        }
+        rest = &rest[end..];
+    }
+    Some(tiles)
+}
+
+/// Get the index of a player written from 1 to `players`
+fn read_player(player : &str, players : usize) -> Result<usize, String> {
+    match player.parse::<usize>().ok() {
+        Some(player) if (1..=players).contains(&player) => Ok(player - 1),
+        _ => Err(format!("The player should be a number from 1 to {}", players)),
+    }
+}
+
+/// Run a command of the editor
+///
+/// # Return Value
+/// Whether the position is done, or what is wrong with the command.
+fn run_command(words : &[&str], position : &mut Position, tileset : &TileSet, dico : &Dico, players : usize)
+        -> Result<bool, String> {
+    let alphabet = tileset.alphabet();
+    match words {
+        ["done"] => {
+            position.check(tileset).map_err(|e| e.to_string())?;
+            return Ok(true);
+        }
+        ["help"] => println!("{}", HELP),
+        ["rack", player, letters] => {
+            let player = read_player(player, players)?;
+            let rack = if *letters == "-" { Some(Vec::new()) } else { read_letters(&letters.to_uppercase(), tileset) };
+            position.set_rack(player, rack.ok_or("This rack has letters that are not in the game")?);
+        }
+        ["bag", "unseen"] => position.set_bag(None),
+        ["bag", letters] => {
+            let bag = read_letters(&letters.to_uppercase(), tileset).ok_or("The bag has letters that are not in the game")?;
+            position.set_bag(Some(bag));
+        }
+        ["best", player, rest @ ..] if rest.len() <= 1 => {
+            let player = read_player(player, players)?;
+            let count = match rest.first() {
+                Some(count) => count.parse().map_err(|_| format!("`{}` is not a number", count))?,
+                None => BEST_COUNT,
+            };
+            let hand = Hand::from_tiles(position.rack(player).to_vec());
+            let moves = best_moves(position.board(), &hand, dico, count);
+            if moves.is_empty() {
+                println!("No move can be made");
+            }
+            for (rank, candidate) in moves.iter().enumerate() {
+                println!("{}. {} : {} points", rank + 1, candidate.mv().notation(alphabet), candidate.score());
+            }
+        }
+        [square, letters] => {
+            let (mut x, mut y) = parse_square(square).ok_or(format!("`{}` is not a spot like H8 or 8H", square))?;
+            // The row first is for a horizontal word
+            let horizontal = square.starts_with(|c : char| c.is_ascii_digit());
+            let tiles = if letters.chars().all(|c| c == EMPTY) {
+                vec![None; letters.len()]
+            }
+            else {
+                read_letters(letters, tileset).ok_or("This word has letters that are not in the game")?
+                    .into_iter().map(Some).collect()
+            };
+            let (end_x, end_y) = if horizontal { (x as usize + tiles.len(), y as usize) } else { (x as usize, y as usize + tiles.len()) };
+            if end_x > 15 || end_y > 15 {
+                return Err("This goes out of the board".to_string());
+            }
+            for tile in tiles {
+                position.set_tile(x, y, tile);
+                if horizontal { x += 1 } else { y += 1 }
+            }
+        }
+        _ => return Err("Unknown command, type help to see them".to_string()),
+    }
+    Ok(false)
+}
+
+/// Let the player set up a position, line by line
+///
+/// The board, the racks and the size of the bag are shown before each
+/// command. The position is given back once `done` is typed and it could
+/// come from a game.
+///
+/// # Arguments
+/// * `position` - Where to start from.
+/// * `players` - How many players have a rack.
+///
+/// # Return Value
+/// The position, None if `input` has nothing more to read.
+pub fn edit_position<R : BufRead>(input : &mut R, position : Position, tileset : &TileSet, dico : &Dico,
+        players : usize) -> Option<Position> {
+    let mut position = position;
+    let alphabet = tileset.alphabet();
+    println!("{}", HELP);
+    loop {
+        print_board(position.board(), alphabet);
+        for player in 0..players {
+            println!("Rack {} :", player + 1);
+            print_hand(&Hand::from_tiles(position.rack(player).to_vec()), alphabet);
+        }
+        println!("{} tiles in the bag", position.bag(tileset).len());
+        println!("Set up the position, or type done :");
+        let line = read_line(input)?;
+        let words : Vec<&str> = line.split_whitespace().collect();
+        match run_command(&words, &mut position, tileset, dico, players) {
+            Ok(true) => return Some(position),
+            Ok(false) => {}
+            Err(e) => handle_error(&e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{edit_position, read_letters};
+    use crate::scrabbleutils::{Board, Dico, Position, TileSet};
+
+    #[test]
+    fn setup() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let dico = Dico::from_file("dico.txt", ab).unwrap();
+        let tiles = read_letters("Ae?", &tileset).unwrap();
+        assert!(!tiles[0].wildcard() && tiles[1].wildcard() && tiles[2].wildcard());
+        assert_eq!(tiles[1].letter(), ab.letter("E").unwrap());
+        assert!(read_letters("A1", &tileset).is_none());
+
+        // A word down, one taken back, a rack, a spot that is not one, then a
+        // Z too many
+        let mut input = "H8 HeLLO\nH11 .\nrack 2 ME?\nrack 3 A\nÉ8 A\n8A ZZ\ndone\n8A ..\nbest 2\ndone\n".as_bytes();
+        let position = edit_position(&mut input, Position::new(Board::new()), &tileset, &dico, 2).unwrap();
+        let board = position.board();
+        assert_eq!(board.get_letter(7, 7), ab.letter("H"));
+        assert!(board.get_tile(7, 8).unwrap().wildcard());
+        assert_eq!(board.get_letter(7, 10), None);
+        assert_eq!(board.get_letter(0, 7), None);
+        assert_eq!(position.rack(1).len(), 3);
+
+        let mut input = "8A ZZ\n".as_bytes();
+        assert!(edit_position(&mut input, Position::new(Board::new()), &tileset, &dico, 2).is_none());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::scrab_study::Quiz;
+use super::read_line;
+use std::io::BufRead;
+
+/// Stops the quiz
+const QUIT : &str = "quit";
+
+/// Ask the questions of `quiz` until the player stops
+///
+/// The words of a rack are typed on one line, an empty line giving up.
+/// `quit`, or nothing more to read, stops the quiz.
+///
+/// # Arguments
+/// * `today` - The day the answers are recorded on, counted from 1970.
+pub fn run_quiz<R : BufRead>(input : &mut R, quiz : &mut Quiz, today : u64) {
+    let mut found = 0;
+    let mut total = 0;
+    println!("Type every word made with all the letters, {} to stop", QUIT);
+    loop {
+        let question = match quiz.next_question(today) {
+            Some(question) => question,
+            None => {
+                println!("No more rack makes a word");
+                break;
+            }
+        };
+        let count = question.answers.len();
+        println!("{} : {} {} to find", question.rack, count, if count > 1 { "words" } else { "word" });
+        let line = match read_line(input) {
+            Some(line) if line.trim() != QUIT => line,
+            _ => break,
+        };
+        let guesses : Vec<&str> = line.split_whitespace().collect();
+        let answer = quiz.answer(&question, &guesses, today);
+        if !answer.found.is_empty() {
+            println!("Found : {}", answer.found.join(" "));
+        }
+        for word in answer.missed.iter() {
+            let stats = quiz.record().stats(word).cloned().unwrap_or_default();
+            println!("Missed : {}, found {} of {} times", word, stats.found, stats.seen);
+        }
+        if !answer.wrong.is_empty() {
+            println!("Not a word of the rack : {}", answer.wrong.join(" "));
+        }
+        found += answer.found.len();
+        total += count;
+    }
+    println!("{} of {} words found", found, total);
+}
+
+#[cfg(test)]
+mod test {
+    use super::run_quiz;
+    use crate::scrab_study::{Quiz, StudyRecord};
+    use crate::scrabbleutils::{Dico, TileSet};
+
+    #[test]
+    fn quiz() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let dico = Dico::from_words(vec!["IT", "TI", "IS"], tileset.alphabet());
+        let mut quiz = Quiz::new(&tileset, &dico, StudyRecord::new(), 2);
+        quiz.set_seed(1);
+        // Whatever the rack, its words are found, then the quiz stops
+        let mut input = "ti it is xx\nquit\n".as_bytes();
+        run_quiz(&mut input, &mut quiz, 100);
+        let asked = quiz.record().due(1000);
+        assert!(!asked.is_empty());
+        for word in asked {
+            let stats = quiz.record().stats(word).unwrap();
+            assert_eq!((stats.seen, stats.found, stats.due), (1, 1, 102));
+        }
+    }
+}
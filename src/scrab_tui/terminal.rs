@@ -0,0 +1,216 @@
+use std::io::{self, stdin, BufRead, Read};
+use std::process::{Command, Stdio};
+
+/// Clear the screen and go to its top left corner
+pub const CLEAR : &str = "\x1b[2J\x1b[H";
+/// Clear the screen and what scrolled out of it
+pub const CLEAR_ALL : &str = "\x1b[H\x1b[2J\x1b[3J";
+/// Go back to the default colors and style
+pub const RESET : &str = "\x1b[0m";
+/// Swap the colors, to show a cursor
+pub const REVERSE : &str = "\x1b[7m";
+pub const BOLD : &str = "\x1b[1m";
+pub const HIDE_CURSOR : &str = "\x1b[?25l";
+pub const SHOW_CURSOR : &str = "\x1b[?25h";
+
+/// Write a text color and a background color, like `30;47` for black on white
+pub fn color(colors : &str) -> String {
+    format!("\x1b[{}m", colors)
+}
+
+/// Go to `row` and `column` of the screen, starting at 1
+pub fn goto(row : usize, column : usize) -> String {
+    format!("\x1b[{};{}H", row, column)
+}
+
+/// Reads the standard input a line at a time, without keeping it locked
+///
+/// The other readers of the standard input get the lines after, nothing is
+/// read in advance.
+#[derive(Default)]
+pub struct StdinLines {
+    line : String,
+    /// How much of the line was consumed
+    pos : usize,
+}
+
+impl StdinLines {
+    pub fn new() -> StdinLines {
+        StdinLines::default()
+    }
+}
+
+impl Read for StdinLines {
+    fn read(&mut self, buf : &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl BufRead for StdinLines {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.line.len() {
+            self.line.clear();
+            self.pos = 0;
+            stdin().read_line(&mut self.line)?;
+        }
+        Ok(&self.line.as_bytes()[self.pos..])
+    }
+
+    fn consume(&mut self, amount : usize) {
+        self.pos += amount;
+    }
+}
+
+/// A key pressed by the user
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key {
+    Char(char),
+    /// A letter typed with the control key held
+    Ctrl(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Backspace,
+    Tab,
+    Escape,
+}
+
+/// Reads keys one by one from the terminal
+pub struct Keys<R : Read> {
+    input : R,
+    /// A byte read after an escape that did not start a sequence
+    pending : Option<u8>,
+}
+
+impl<R : Read> Keys<R> {
+    pub fn new(input : R) -> Keys<R> {
+        Keys {
+            input,
+            pending : None,
+        }
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        if let Some(byte) = self.pending.take() {
+            return Some(byte);
+        }
+        let mut buffer = [0u8; 1];
+        match self.input.read(&mut buffer) {
+            Ok(1) => Some(buffer[0]),
+            _ => None,
+        }
+    }
+
+    /// Read the rest of a character starting with `first`
+    fn utf8(&mut self, first : u8) -> Option<char> {
+        let length = match first {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => return None,
+        };
+        let mut bytes = vec![first];
+        for _ in 1..length {
+            bytes.push(self.byte()?);
+        }
+        std::str::from_utf8(&bytes).ok()?.chars().next()
+    }
+
+    /// Wait for the next key
+    ///
+    /// # Return Value
+    /// None if there is nothing more to read.
+    pub fn next_key(&mut self) -> Option<Key> {
+        loop {
+            let key = match self.byte()? {
+                b'\r' | b'\n' => Key::Enter,
+                b'\t' => Key::Tab,
+                0x7f | 0x08 => Key::Backspace,
+                0x1b => match self.byte() {
+                    Some(b'[') | Some(b'O') => match self.byte()? {
+                        b'A' => Key::Up,
+                        b'B' => Key::Down,
+                        b'C' => Key::Right,
+                        b'D' => Key::Left,
+                        // Other sequences are not used
+                        _ => continue,
+                    },
+                    other => {
+                        self.pending = other;
+                        Key::Escape
+                    }
+                },
+                byte @ 0x01..=0x1a => Key::Ctrl((b'a' + byte - 1) as char),
+                byte if byte < 0x80 => Key::Char(byte as char),
+                byte => match self.utf8(byte) {
+                    Some(c) => Key::Char(c),
+                    None => continue,
+                },
+            };
+            return Some(key);
+        }
+    }
+}
+
+/// Run `stty` on the terminal of the standard input
+fn stty(args : &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("the input is not a terminal"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The terminal gives each key as it is pressed, without showing it
+///
+/// The terminal is set back the way it was when this is dropped.
+pub struct RawMode {
+    /// The settings of the terminal before
+    saved : String,
+}
+
+impl RawMode {
+    /// Put the terminal of the standard input in raw mode
+    ///
+    /// # Errors
+    /// If the standard input is not a terminal.
+    pub fn enable() -> io::Result<RawMode> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        Ok(RawMode {
+            saved,
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Keys, Key};
+
+    #[test]
+    fn keys() {
+        let mut keys = Keys::new("a\x1b[A\x1b[D\r\x7f\x10\té\x1bq".as_bytes());
+        let expected = [Key::Char('a'), Key::Up, Key::Left, Key::Enter, Key::Backspace,
+            Key::Ctrl('p'), Key::Tab, Key::Char('é'), Key::Escape, Key::Char('q')];
+        for key in expected.iter() {
+            assert_eq!(keys.next_key(), Some(*key));
+        }
+        assert_eq!(keys.next_key(), None);
+    }
+}
@@ -0,0 +1,128 @@
+/// Index of a letter in an `Alphabet`
+///
+/// This is what tiles, moves, the board and the dictionnary work on.
+pub type Letter = u8;
+
+/// Accented letters and what they are written with when the alphabet does
+/// not have them
+const FOLDS : [(char, &str); 28] = [
+    ('À', "A"), ('Á', "A"), ('Â', "A"), ('Ã', "A"), ('Ä', "A"), ('Å', "A"),
+    ('Æ', "AE"), ('Ç', "C"), ('È', "E"), ('É', "E"), ('Ê', "E"), ('Ë', "E"),
+    ('Ì', "I"), ('Í', "I"), ('Î', "I"), ('Ï', "I"), ('Ñ', "N"), ('Ò', "O"),
+    ('Ó', "O"), ('Ô', "O"), ('Õ', "O"), ('Ö', "O"), ('Œ', "OE"), ('Ù', "U"),
+    ('Ú', "U"), ('Û', "U"), ('Ü', "U"), ('Ÿ', "Y"),
+];
+
+/// The letters of a game
+///
+/// A letter is written with one or more characters, like `CH` in spanish or
+/// `L·L` in catalan. Each one is known by its index, a `Letter`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alphabet {
+    /// The uppercased letters, a `Letter` is an index in it
+    labels : Vec<String>,
+}
+
+impl Alphabet {
+    /// Create an alphabet
+    ///
+    /// # Argument
+    /// * `labels` - How each letter is written, in the order of their index
+    ///
+    /// # Panic
+    /// If there are more than 256 letters.
+    pub fn new(labels : Vec<String>) -> Alphabet {
+        assert!(labels.len() <= Letter::MAX as usize + 1, "too many letters");
+        Alphabet {
+            labels : labels.iter().map(|l| l.to_uppercase()).collect(),
+        }
+    }
+
+    /// Get the number of letters
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Whether there is no letter
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Get all the letters
+    pub fn letters(&self) -> impl Iterator<Item = Letter> {
+        0..self.labels.len() as Letter
+    }
+
+    /// Get the way `letter` is written, `?` if it is not in the alphabet
+    pub fn label(&self, letter : Letter) -> &str {
+        self.labels.get(letter as usize).map_or("?", |l| l.as_str())
+    }
+
+    /// Get the letter written `label`
+    pub fn letter(&self, label : &str) -> Option<Letter> {
+        let label = label.to_uppercase();
+        self.labels.iter().position(|l| *l == label).map(|i| i as Letter)
+    }
+
+    /// Split a word into letters
+    ///
+    /// The word is uppercased, the longest letter matching is taken first
+    /// (`CHE` is `CH`, `E` in spanish). Accents are removed from letters that
+    /// are not in the alphabet (`É` is `E` in french but `Ä` stays in german).
+    ///
+    /// # Return Value
+    /// None if a part of the word is not in the alphabet.
+    pub fn encode(&self, word : &str) -> Option<Vec<Letter>> {
+        let mut rest = word.to_uppercase();
+        let mut letters : Vec<Letter> = Vec::with_capacity(rest.len());
+        while !rest.is_empty() {
+            let longest = self.labels.iter().enumerate()
+                .filter(|(_, label)| !label.is_empty() && rest.starts_with(label.as_str()))
+                .max_by_key(|(_, label)| label.len());
+            match longest {
+                Some((letter, label)) => {
+                    letters.push(letter as Letter);
+                    rest.drain(..label.len());
+                }
+                None => {
+                    let c = rest.chars().next().unwrap();
+                    let folded = FOLDS.iter().find(|f| f.0 == c)?.1;
+                    rest.replace_range(..c.len_utf8(), folded);
+                }
+            }
+        }
+        Some(letters)
+    }
+
+    /// Write letters as a string
+    pub fn decode(&self, letters : &[Letter]) -> String {
+        letters.iter().map(|l| self.label(*l)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Alphabet;
+
+    fn alphabet(labels : &[&str]) -> Alphabet {
+        Alphabet::new(labels.iter().map(|l| l.to_string()).collect())
+    }
+
+    #[test]
+    fn digraphs() {
+        let spanish = alphabet(&["A", "C", "CH", "E", "L", "LL", "O", "Ñ"]);
+        assert_eq!(spanish.encode("coche"), Some(vec![1, 6, 2, 3]));
+        assert_eq!(spanish.encode("calle"), Some(vec![1, 0, 5, 3]));
+        assert_eq!(spanish.encode("año"), Some(vec![0, 7, 6]));
+        assert_eq!(spanish.decode(&[2, 0]), "CHA");
+        assert_eq!(spanish.encode("HOLA"), None);
+    }
+
+    #[test]
+    fn accents() {
+        let french = alphabet(&["A", "E", "T", "Ô"]);
+        assert_eq!(french.encode("été"), Some(vec![1, 2, 1]));
+        assert_eq!(french.encode("tôt"), Some(vec![2, 3, 2]));
+        assert_eq!(french.encode("tät"), Some(vec![2, 0, 2]));
+    }
+}
@@ -0,0 +1,239 @@
+use super::{Board, Dico, Hand, Move, TileSet, Alphabet, GameRecord, Play, generate_moves};
+
+/// How a turn compares with the best move the hand could make
+pub struct TurnAnalysis {
+    /// The index of the turn in the record, starting at 0
+    pub turn : usize,
+    pub player : usize,
+    pub play : Play,
+    /// The points the turn made
+    pub score : u32,
+    /// The best scoring move, None if no move could be made
+    pub best : Option<Move>,
+    pub best_score : u32,
+    /// Whether a bingo could be made and was not
+    pub missed_bingo : bool,
+}
+
+impl TurnAnalysis {
+    /// Get the points lost by not making the best move
+    pub fn lost(&self) -> u32 {
+        self.best_score.saturating_sub(self.score)
+    }
+}
+
+/// How well a player played the whole game
+pub struct PlayerSummary {
+    pub name : String,
+    /// The number of turns analyzed
+    pub turns : usize,
+    pub scored : u32,
+    /// What the best moves would have scored
+    pub best : u32,
+    pub missed_bingos : usize,
+}
+
+impl PlayerSummary {
+    pub fn lost(&self) -> u32 {
+        self.best.saturating_sub(self.scored)
+    }
+
+    /// Get the share of the best points that was scored, in percent
+    pub fn accuracy(&self) -> f64 {
+        if self.best == 0 {
+            return 100.0;
+        }
+        self.scored as f64 * 100.0 / self.best as f64
+    }
+}
+
+/// Every turn of a game compared with the best move
+///
+/// Only the points are compared: the tiles kept for the next turns are not
+/// taken into account.
+pub struct GameAnalysis {
+    pub turns : Vec<TurnAnalysis>,
+    /// One for each player, in the order they play
+    pub players : Vec<PlayerSummary>,
+}
+
+/// Write `text` as a JSON string
+fn json_string(text : &str) -> String {
+    let mut json = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Write what was played, in the notation of GCG files
+fn play_notation(play : &Play, alphabet : &Alphabet) -> String {
+    match play {
+        Play::Placed(mv) => mv.notation(alphabet),
+        Play::Exchanged(count) => format!("-{}", count),
+        Play::Passed => "-".to_string(),
+        Play::Leftover(tiles) => format!("({} tiles)", tiles.len()),
+        Play::Adjustment(reason) => reason.clone(),
+    }
+}
+
+impl GameAnalysis {
+    /// Replay a game, looking for the best move of each turn
+    ///
+    /// The turns whose rack is not known, and the tiles left and adjustments
+    /// at the end of the game, are left out.
+    ///
+    /// # Arguments
+    /// * `record` - The game, made by a `GameRecorder` or read from a GCG file.
+    /// * `board` - The empty board the game was played on.
+    /// * `tileset` - The tiles of the game.
+    /// * `dico` - The words allowed.
+    pub fn new(record : &GameRecord, mut board : Board, tileset : &TileSet, dico : &Dico) -> GameAnalysis {
+        let mut players : Vec<PlayerSummary> = record.players.iter()
+            .map(|name| PlayerSummary { name : name.clone(), turns : 0, scored : 0, best : 0, missed_bingos : 0 })
+            .collect();
+        let mut turns : Vec<TurnAnalysis> = Vec::new();
+        for (index, turn) in record.turns.iter().enumerate() {
+            let tiles = turn.tiles(&board, tileset);
+            if turn.rack.is_empty() || matches!(turn.play, Play::Leftover(_) | Play::Adjustment(_)) {
+                if let Play::Placed(mv) = &turn.play {
+                    board.add_move(mv.clone(), tiles);
+                }
+                continue;
+            }
+            let candidates = generate_moves(&board, &Hand::from_tiles(turn.rack.clone()), dico);
+            let is_bingo = |mv : &Move, tiles : &[_]| board.score_breakdown(mv, tiles).bingo > 0;
+            let played_bingo = match &turn.play {
+                Play::Placed(mv) => is_bingo(mv, &tiles),
+                _ => false,
+            };
+            let bingo = candidates.iter().any(|c| is_bingo(c.mv(), c.tiles()));
+            let best = candidates.iter().max_by_key(|c| c.score());
+            let analysis = TurnAnalysis {
+                turn : index,
+                player : turn.player,
+                play : turn.play.clone(),
+                score : turn.score.max(0) as u32,
+                best : best.map(|c| c.mv().clone()),
+                best_score : best.map_or(0, |c| c.score()),
+                missed_bingo : bingo && !played_bingo,
+            };
+            let summary = &mut players[turn.player];
+            summary.turns += 1;
+            summary.scored += analysis.score;
+            summary.best += analysis.best_score.max(analysis.score);
+            summary.missed_bingos += analysis.missed_bingo as usize;
+            turns.push(analysis);
+            if let Play::Placed(mv) = &turn.play {
+                board.add_move(mv.clone(), tiles);
+            }
+        }
+        GameAnalysis {
+            turns,
+            players,
+        }
+    }
+
+    /// Write the analysis to be read in a terminal
+    pub fn to_text(&self, alphabet : &Alphabet) -> String {
+        let mut text = String::new();
+        for turn in self.turns.iter() {
+            let name = &self.players[turn.player].name;
+            let play = match &turn.play {
+                Play::Placed(mv) => format!("played {} for {}", mv.notation(alphabet), turn.score),
+                Play::Exchanged(count) => format!("exchanged {} tiles", count),
+                Play::Passed => "passed".to_string(),
+                Play::Leftover(tiles) => format!("had {} tiles left", tiles.len()),
+                Play::Adjustment(reason) => reason.clone(),
+            };
+            text.push_str(&format!("Turn {}, {} {}", turn.turn + 1, name, play));
+            match &turn.best {
+                Some(best) if turn.lost() > 0 => text.push_str(&format!(", {} made {}, {} points lost",
+                    best.notation(alphabet), turn.best_score, turn.lost())),
+                Some(_) => text.push_str(", the best move"),
+                None => text.push_str(", no move could be made"),
+            }
+            if turn.missed_bingo {
+                text.push_str(", a bingo was missed");
+            }
+            text.push('\n');
+        }
+        for player in self.players.iter() {
+            text.push_str(&format!("{} : {} of {} points in {} turns, {:.1}% accuracy, {} points lost, {} bingos missed\n",
+                player.name, player.scored, player.best, player.turns, player.accuracy(), player.lost(),
+                player.missed_bingos));
+        }
+        text
+    }
+
+    /// Write the analysis in JSON, for other programs
+    pub fn to_json(&self, alphabet : &Alphabet) -> String {
+        let turns : Vec<String> = self.turns.iter().map(|turn| {
+            let best = match &turn.best {
+                Some(best) => json_string(&best.notation(alphabet)),
+                None => "null".to_string(),
+            };
+            format!("{{\"turn\":{},\"player\":{},\"played\":{},\"score\":{},\"best\":{},\"best_score\":{},\"lost\":{},\"missed_bingo\":{}}}",
+                turn.turn + 1, json_string(&self.players[turn.player].name),
+                json_string(&play_notation(&turn.play, alphabet)), turn.score, best, turn.best_score,
+                turn.lost(), turn.missed_bingo)
+        }).collect();
+        let players : Vec<String> = self.players.iter().map(|player| {
+            format!("{{\"name\":{},\"turns\":{},\"scored\":{},\"best\":{},\"lost\":{},\"missed_bingos\":{},\"accuracy\":{:.1}}}",
+                json_string(&player.name), player.turns, player.scored, player.best, player.lost(),
+                player.missed_bingos, player.accuracy())
+        }).collect();
+        format!("{{\"players\":[{}],\"turns\":[{}]}}", players.join(","), turns.join(","))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Board, Dico, TileSet, GameRecord};
+    use super::GameAnalysis;
+
+    #[test]
+    fn analysis() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let dico = Dico::from_file("dico.txt", ab).unwrap();
+        let gcg = "#player1 ann Ann\n#player2 bob Bob \"B\"\n\
+            >ann: HELLOIT 8G IT +4 4\n\
+            >bob: AAUUYYV - +0 0\n\
+            >ann: SELLOH G8 IS +3 7\n\
+            >ann: (time) -10 0\n";
+        let record = GameRecord::from_gcg(gcg.as_bytes(), &tileset).unwrap();
+        let analysis = GameAnalysis::new(&record, Board::new(), &tileset, &dico);
+
+        // The adjustment is left out
+        assert_eq!(analysis.turns.len(), 3);
+        let first = &analysis.turns[0];
+        assert_eq!(first.best_score, 24);
+        assert_eq!(first.lost(), 20);
+        assert!(first.best.is_some());
+        assert!(!first.missed_bingo);
+        // Nothing could be made with those
+        assert!(analysis.turns[1].best.is_none());
+        assert_eq!(analysis.turns[1].lost(), 0);
+
+        let ann = &analysis.players[0];
+        assert_eq!((ann.turns, ann.scored, ann.lost()), (2, 7, ann.best - 7));
+        assert!(ann.accuracy() < 100.0);
+        assert_eq!(analysis.players[1].accuracy(), 100.0);
+
+        let text = analysis.to_text(ab);
+        assert!(text.starts_with("Turn 1, Ann played 8G IT for 4, "));
+        assert!(text.contains("HELLO made 24, 20 points lost"));
+        let json = analysis.to_json(ab);
+        assert!(json.starts_with("{\"players\":[{\"name\":\"Ann\",\"turns\":2,\"scored\":7,"));
+        assert!(json.contains("{\"name\":\"Bob \\\"B\\\"\""));
+        assert!(json.contains("\"played\":\"-\",\"score\":0,\"best\":null"));
+    }
+}
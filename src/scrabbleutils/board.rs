@@ -1,16 +1,40 @@
 use super::spot::Spot;
 use super::bonuses::{WordBonus, LetterBonus};
-use super::{Move, Direction, Tile};
+use super::{Move, Direction, Tile, Letter};
+
+/// Amount of tiles to place at once to get the bingo bonus
+const BINGO_LENGTH : usize = 7;
+/// Points given for a bingo
+const BINGO_BONUS : u32 = 50;
+/// The row and column of the center square
+const CENTER : u8 = 7;
 
 /// The board we're playing on
 ///
 /// It contains an array of `Spot` and provide shortcuts to interract with them.
 /// All positions range start at 0.
 /// The ordinate position 0 is considered at the top.
+#[derive(Clone)]
 pub struct Board {
     spots: Vec<Spot>,
 }
 
+/// How the score of a move is made
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreBreakdown {
+    /// Each word made and its score, the word of the move first
+    pub words : Vec<(Vec<Letter>, u32)>,
+    /// The bonus for placing the whole hand, 0 otherwise
+    pub bingo : u32,
+}
+
+impl ScoreBreakdown {
+    /// Get the score of the move
+    pub fn total(&self) -> u32 {
+        self.words.iter().map(|w| w.1).sum::<u32>() + self.bingo
+    }
+}
+
 impl Board {
     /// Create a new board
     ///
@@ -91,8 +115,8 @@ impl Board {
     /// * `y` - the ordinate position on the board.
     ///
     /// # Return Value
-    /// An `Option<char>` where None is in the case there is no tile on this spot
-    pub fn get_letter(&self, x : u8, y : u8) -> Option<char> {
+    /// An `Option<Letter>` where None is in the case there is no tile on this spot
+    pub fn get_letter(&self, x : u8, y : u8) -> Option<Letter> {
         if x >= 15 || y >= 15 {
             return None;
         }
@@ -117,6 +141,16 @@ impl Board {
         return spot.tile.clone();
     }
 
+    /// Put a tile on the spot at position (`x`, `y`), or clear it with None
+    ///
+    /// Nothing is checked, this is for boards copied from elsewhere.
+    ///
+    /// # Panic
+    /// If `x` or `y` are greater or equal to 15.
+    pub fn set_tile(&mut self, x : u8, y : u8, tile : Option<Tile>) {
+        self.get_spot_mut(x, y).tile = tile;
+    }
+
     /// Whether it is possible to play this move
     ///
     /// Warning : The move should not be added to the board before calling this
@@ -140,7 +174,7 @@ impl Board {
         match mv.direction() {
             Direction::Horizontal => {
                 // Test for an out of array move
-                if pos_x as usize + mv.word().chars().count() >= 15 {
+                if pos_x as usize + mv.word().len() > 15 {
                     return false;
                 }
                 offset_x = 1;
@@ -148,7 +182,7 @@ impl Board {
             }
             Direction::Vertical => {
                 // Test for an out of array move
-                if pos_y as usize + mv.word().chars().count() >= 15 {
+                if pos_y as usize + mv.word().len() > 15 {
                     return false;
                 }
                 offset_x = 0;
@@ -157,13 +191,12 @@ impl Board {
         }
 
         // Iterator over the move's word
-        let word_it : Vec<char> = mv.word().chars().collect();
-        for c in word_it {
+        for c in mv.word() {
             let board_letter = self.get_letter(pos_x, pos_y);
             // If there is a letter on the board at this spot
             if let Some(letter) = board_letter {
                 // Make sure it matches with the current word
-                if letter != c {
+                if letter != *c {
                     return false;
                 }
             }
@@ -182,20 +215,19 @@ impl Board {
     /// * `mv` - The move the player wants to make
     ///
     /// # Return Value
-    /// A vector of chars
-    pub fn needed_letters(&self, mv : &Move) -> Vec<char> {
+    /// A vector of letters
+    pub fn needed_letters(&self, mv : &Move) -> Vec<Letter> {
         // The returned value
-        let mut letters : Vec<char> = Vec::with_capacity(
-                mv.word().chars().count() - 1);
+        let mut letters : Vec<Letter> = Vec::with_capacity(mv.word().len());
         // The offset we'll add in every loop
         let offset_x : u8;
         let offset_y : u8;
         // The counter to the actual position on the board
         let mut pos_x = mv.x();
         let mut pos_y = mv.y();
-        // char iterator and storage for next() return value
-        let mut word_it = mv.word().chars();
-        let mut next_char : Option<char>;
+        // letter iterator and storage for next() return value
+        let mut word_it = mv.word().iter().copied();
+        let mut next_char : Option<Letter>;
 
         match mv.direction() {
             Direction::Horizontal => {
@@ -250,10 +282,9 @@ impl Board {
             }
         }
 
-        let chars = mv.word().chars();
         let mut tiles_it = tiles.into_iter();
-        // For each char in the word
-        for c in chars {
+        // For each letter in the word
+        for c in mv.word().iter().copied() {
             // If we need to add a tile to the current spot
             if self.get_letter(pos_x, pos_y) == None {
                 let mut tile : Tile = tiles_it.next().unwrap();
@@ -268,128 +299,279 @@ impl Board {
         }
     }
 
-    /// Get the score of a word made perpendicularly
-    ///
-    /// It should be called whenever a tile is being set with at least one
-    /// perpendicular neighbor around.
+    /// Get the score of the word made perpendicularly to a new tile
     ///
     /// # Arguments
-    /// * `initial_x` - Absciss position somewhere in the perpendicular word
-    /// * `initial_y` - Ordinate position somewhere in the perpendicular word
+    /// * `x` - Absciss position of the added tile
+    /// * `y` - Ordinate position of the added tile
     /// * `direction` - The direction the original word was
     /// * `added` - The added tile at this position
     ///
     /// # Return Value
-    /// The score the perpendicular word made including the added letter
-    fn perp_score(&self, initial_x : u8, initial_y : u8, direction : Direction,
-            added : &Tile) -> u32 {
-        assert!(initial_x < 15);
-        assert!(initial_y < 15);
-        let mut score = 0;
-
-        let mut pos_x = initial_x as i16;
-        let mut pos_y = initial_y as i16;
-
-        let offset_x : i16;
-        let offset_y : i16;
-
-        match direction {
-            Direction::Horizontal => {
-                offset_x = 1;
-                offset_y = 0;
-            }
-            Direction::Vertical => {
-                offset_x = 0;
-                offset_y = 1;
+    /// The score the perpendicular word made including the bonuses under the
+    /// added tile, or None if the tile has no perpendicular neighbor.
+    fn perp_score(&self, x : u8, y : u8, direction : Direction,
+            added : &Tile) -> Option<u32> {
+        let (offset_x, offset_y) : (i16, i16) = match direction {
+            Direction::Horizontal => (0, 1),
+            Direction::Vertical => (1, 0),
+        };
+        let mut neighbors_score = 0;
+        let mut has_neighbor = false;
+
+        // Walk both ways from the added tile until we find an empty spot
+        for way in [-1i16, 1].iter() {
+            let mut pos_x = x as i16 + way * offset_x;
+            let mut pos_y = y as i16 + way * offset_y;
+            while pos_x >= 0 && pos_y >= 0 {
+                match self.get_tile(pos_x as u8, pos_y as u8) {
+                    None => break,
+                    Some(tile) => {
+                        has_neighbor = true;
+                        neighbors_score += tile.points() as u32;
+                    }
+                }
+                pos_x += way * offset_x;
+                pos_y += way * offset_y;
             }
         }
 
-        // Initial step, count the number of point of the added letter
-        score += added.points() as u32;
-
-        // Then count the previous perpendicular letters
-        pos_x -= offset_y;
-        pos_y -= offset_x;
-
-        while pos_x >= 0 && pos_y >= 0 && pos_y < 15 &&
-                self.get_letter(pos_x as u8, pos_y as u8) != None {
-            score += self.get_tile(pos_x as u8, pos_y as u8)
-                .unwrap().points() as u32;
-            pos_x -= offset_y;
-            pos_y -= offset_x;
-        }
-
-        // Then get the nexts perpendicular letters
-        pos_x = initial_x as i16 + offset_y;
-        pos_y = initial_y as i16 + offset_x;
-        while pos_x < 15 && pos_y < 15 &&
-                self.get_letter(pos_x as u8, pos_y as u8) != None {
-            score += self.get_tile(pos_x as u8, pos_y as u8).unwrap().points() as u32;
-            pos_x += offset_y;
-            pos_y += offset_x;
+        if !has_neighbor {
+            return None;
         }
-
-        score
+        let (lb, wb) = self.get_spot(x, y).get_bonuses_value();
+        Some((neighbors_score + lb * added.points() as u32) * wb)
     }
 
     /// Get the score of a move
     ///
-    /// This function need sources for the way of calculating the points.
-    /// It seems almost standard but it's good to have a rule somewhere.
+    /// See `score_breakdown()` for how it is counted.
     ///
     /// # Arguments
     /// * `mv` - The move the player wants to make
     /// * `removed` - The tiles the player removed from its hand to play.
     /// You can get it by using `needed_letters()` and `Hand::remove()`
-    pub fn score(&self, mv : &Move, removed : &Vec<Tile>) -> u32 {
+    pub fn score(&self, mv : &Move, removed : &[Tile]) -> u32 {
+        self.score_breakdown(mv, removed).total()
+    }
+
+    /// Get the score of each word a move makes
+    ///
+    /// The word's letters are summed, letter bonuses under the new tiles are
+    /// applied, then the word bonuses under the new tiles. Every word made
+    /// perpendicularly by a new tile is scored the same way.
+    /// Placing the whole hand (7 tiles) gives 50 more points.
+    ///
+    /// The move should not be added to the board yet.
+    ///
+    /// # Arguments
+    /// * `mv` - The move the player wants to make
+    /// * `removed` - The tiles the player removed from its hand to play.
+    pub fn score_breakdown(&self, mv : &Move, removed : &[Tile]) -> ScoreBreakdown {
         let mut score = 0;
+        let mut perp_scores : Vec<u32> = Vec::new();
         let mut word_bonus = 1;
-        let mut tile_score : u32;
 
         let mut pos_x = mv.x();
         let mut pos_y = mv.y();
-        let offset_x : u8;
-        let offset_y : u8;
-
-        match mv.direction() {
-            Direction::Horizontal => {
-                offset_x = 1;
-                offset_y = 0;
-            }
-            Direction::Vertical => {
-                offset_x = 0;
-                offset_y = 1;
-            }
-        }
+        let (offset_x, offset_y) = match mv.direction() {
+            Direction::Horizontal => (1, 0),
+            Direction::Vertical => (0, 1),
+        };
 
         let mut remove_it = removed.iter();
-        let chars = mv.word().chars();
-        for _ in chars {
+        for _ in mv.word() {
             let current_spot = self.get_spot(pos_x, pos_y);
             match &current_spot.tile {
                 None => {
-                    // get the bonuses
                     let removed_tile = remove_it.next().unwrap();
                     let (lb, wb) = current_spot.get_bonuses_value();
                     word_bonus *= wb;
-                    if self.get_letter(pos_x + offset_y, pos_y + offset_x) == None ||
-                       self.get_letter(pos_x - offset_y, pos_y - offset_x) == None {
-                        tile_score = self.perp_score(pos_x, pos_y, mv.direction(), removed_tile);
-                    }
-                    else {
-                        tile_score = lb * removed_tile.points() as u32;
-                    }
-
+                    score += lb * removed_tile.points() as u32;
+                    perp_scores.extend(self.perp_score(pos_x, pos_y,
+                        mv.direction(), removed_tile));
                 }
                 Some(tile) => {
-                    tile_score = tile.points() as u32;
+                    score += tile.points() as u32;
                 }
             }
-            score += tile_score;
             pos_x += offset_x;
             pos_y += offset_y;
         }
-        score * word_bonus
+
+        // The perpendicular words come in the same order as their scores
+        let scores = std::iter::once(score * word_bonus).chain(perp_scores);
+        ScoreBreakdown {
+            words : self.formed_words(mv).into_iter().zip(scores).collect(),
+            bingo : if removed.len() == BINGO_LENGTH { BINGO_BONUS } else { 0 },
+        }
+    }
+
+    /// Get the words a move makes
+    ///
+    /// The move should not be added to the board yet.
+    ///
+    /// # Arguments
+    /// * `mv` - The move the player wants to make
+    ///
+    /// # Return Value
+    /// The word of the move followed by the words made perpendicularly by
+    /// the new tiles.
+    pub fn formed_words(&self, mv : &Move) -> Vec<Vec<Letter>> {
+        let mut words = vec![mv.word().to_vec()];
+        let (offset_x, offset_y) : (i16, i16) = match mv.direction() {
+            Direction::Horizontal => (1, 0),
+            Direction::Vertical => (0, 1),
+        };
+
+        for (i, c) in mv.word().iter().enumerate() {
+            let x = mv.x() as i16 + i as i16 * offset_x;
+            let y = mv.y() as i16 + i as i16 * offset_y;
+            if x >= 15 || y >= 15 || self.get_letter(x as u8, y as u8).is_some() {
+                continue;
+            }
+            // Perpendicular letters before and after the new tile
+            let mut before : Vec<Letter> = Vec::new();
+            let (mut pos_x, mut pos_y) = (x - offset_y, y - offset_x);
+            while pos_x >= 0 && pos_y >= 0 {
+                match self.get_letter(pos_x as u8, pos_y as u8) {
+                    Some(letter) => before.insert(0, letter),
+                    None => break,
+                }
+                pos_x -= offset_y;
+                pos_y -= offset_x;
+            }
+            let mut after : Vec<Letter> = Vec::new();
+            let (mut pos_x, mut pos_y) = (x + offset_y, y + offset_x);
+            while let Some(letter) = self.get_letter(pos_x as u8, pos_y as u8) {
+                after.push(letter);
+                pos_x += offset_y;
+                pos_y += offset_x;
+            }
+            if !before.is_empty() || !after.is_empty() {
+                before.push(*c);
+                before.append(&mut after);
+                words.push(before);
+            }
+        }
+        words
+    }
+
+    /// Get the position of each letter of a move, in order
+    ///
+    /// The positions past the edge of the board are not given.
+    fn positions(mv : &Move) -> Vec<(u8, u8)> {
+        let (offset_x, offset_y) = match mv.direction() {
+            Direction::Horizontal => (1, 0),
+            Direction::Vertical => (0, 1),
+        };
+        (0..mv.word().len() as u8)
+            .map(|i| (mv.x() + i * offset_x, mv.y() + i * offset_y))
+            .filter(|(x, y)| *x < 15 && *y < 15)
+            .collect()
+    }
+
+    /// Whether no tile was placed on the board yet
+    pub fn is_empty(&self) -> bool {
+        self.spots.iter().all(|spot| spot.tile.is_none())
+    }
+
+    /// Whether a move covers the center of the board, where the first word
+    /// must be placed
+    pub fn covers_center(&self, mv : &Move) -> bool {
+        Board::positions(mv).contains(&(CENTER, CENTER))
+    }
+
+    /// Whether a move goes through a tile of the board or lays a tile next
+    /// to one
+    pub fn touches(&self, mv : &Move) -> bool {
+        Board::positions(mv).into_iter().any(|(x, y)| {
+            // Out of the board, the positions wrap to 255 and have no letter
+            let around = [(x, y), (x.wrapping_sub(1), y), (x + 1, y),
+                (x, y.wrapping_sub(1)), (x, y + 1)];
+            around.iter().any(|(x, y)| self.get_letter(*x, *y).is_some())
+        })
+    }
+
+    /// Whether the board has a letter right before or after the word of a
+    /// move, the word really made is then longer
+    pub fn is_extended(&self, mv : &Move) -> bool {
+        let length = mv.word().len() as u8;
+        let (before, after) = match mv.direction() {
+            Direction::Horizontal => ((mv.x().wrapping_sub(1), mv.y()), (mv.x() + length, mv.y())),
+            Direction::Vertical => ((mv.x(), mv.y().wrapping_sub(1)), (mv.x(), mv.y() + length)),
+        };
+        self.get_letter(before.0, before.1).is_some() || self.get_letter(after.0, after.1).is_some()
+    }
+
+    /// Get the move made by laying letters on empty spots
+    ///
+    /// The letters must be in one line, the letters of the board in and
+    /// around it are taken into the word. A single letter goes along the
+    /// word it touches, horizontally if it touches none or both.
+    ///
+    /// # Argument
+    /// * `placed` - The position of each new letter, and the letter
+    ///
+    /// # Return Value
+    /// None if nothing is placed, if a spot is taken twice or not free, or
+    /// if the letters are not in one line without holes.
+    pub fn placed_move(&self, placed : &[(u8, u8, Letter)]) -> Option<Move> {
+        let (first_x, first_y, _) = *placed.first()?;
+        let mut positions : Vec<(u8, u8)> = Vec::with_capacity(placed.len());
+        for (x, y, _) in placed.iter() {
+            if *x >= 15 || *y >= 15 || self.get_letter(*x, *y).is_some()
+                    || positions.contains(&(*x, *y)) {
+                return None;
+            }
+            positions.push((*x, *y));
+        }
+        let horizontal = if placed.len() == 1 {
+            let touches = |x : i16, y : i16| x >= 0 && y >= 0
+                && self.get_letter(x as u8, y as u8).is_some();
+            let (x, y) = (first_x as i16, first_y as i16);
+            touches(x - 1, y) || touches(x + 1, y) || !(touches(x, y - 1) || touches(x, y + 1))
+        }
+        else if placed.iter().all(|p| p.1 == first_y) {
+            true
+        }
+        else if placed.iter().all(|p| p.0 == first_x) {
+            false
+        }
+        else {
+            return None;
+        };
+
+        // Walk the line as (position on the line, fixed coordinate)
+        let line = |pos : u8| if horizontal { (pos, first_y) } else { (first_x, pos) };
+        let along = |p : &(u8, u8, Letter)| if horizontal { p.0 } else { p.1 };
+        let mut start = placed.iter().map(along).min()?;
+        let mut end = placed.iter().map(along).max()?;
+        while start > 0 && self.get_letter(line(start - 1).0, line(start - 1).1).is_some() {
+            start -= 1;
+        }
+        while self.get_letter(line(end + 1).0, line(end + 1).1).is_some() {
+            end += 1;
+        }
+        let mut word : Vec<Letter> = Vec::with_capacity((end - start + 1) as usize);
+        for pos in start..=end {
+            let (x, y) = line(pos);
+            let letter = match placed.iter().find(|p| p.0 == x && p.1 == y) {
+                Some(p) => p.2,
+                None => self.get_letter(x, y)?,
+            };
+            word.push(letter);
+        }
+        let (x, y) = line(start);
+        let direction = if horizontal { Direction::Horizontal } else { Direction::Vertical };
+        Some(Move::new(x, y, word, direction))
+    }
+
+    /// Change the bonuses of a spot, to build other layouts
+    pub(super) fn set_bonuses(&mut self, x : u8, y : u8, letter : LetterBonus, word : WordBonus) {
+        let spot = self.get_spot_mut(x, y);
+        spot.bonus_letter = letter;
+        spot.bonus_word = word;
     }
 
     /// Get the bonuses for a given tile
@@ -408,3 +590,86 @@ impl Board {
         return self.get_spot(x, y).get_bonuses();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::super::{Move, Direction, Tile, TileSet, Alphabet};
+    use super::Board;
+
+    fn word(alphabet : &Alphabet, word : &str) -> Vec<u8> {
+        alphabet.encode(word).unwrap()
+    }
+
+    fn tiles(alphabet : &Alphabet, letters : &str) -> Vec<Tile> {
+        word(alphabet, letters).into_iter().map(|l| Tile::new(l, 1, false)).collect()
+    }
+
+    #[test]
+    fn can_place_edges() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let board = Board::new();
+        // The last letter can be on the last column or row
+        assert!(board.can_place(&Move::new(10, 0, word(ab, "HELLO"), Direction::Horizontal)));
+        assert!(board.can_place(&Move::new(0, 10, word(ab, "HELLO"), Direction::Vertical)));
+        assert!(!board.can_place(&Move::new(11, 0, word(ab, "HELLO"), Direction::Horizontal)));
+        assert!(!board.can_place(&Move::new(0, 11, word(ab, "HELLO"), Direction::Vertical)));
+    }
+
+    #[test]
+    fn words_and_scores() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let mut board = Board::new();
+        let hello = Move::new(5, 7, word(ab, "HELLO"), Direction::Horizontal);
+        // The center is a double word
+        assert_eq!(board.score(&hello, &tiles(ab, "HELLO")), 10);
+        board.add_move(hello, tiles(ab, "HELLO"));
+
+        // M on a double letter above the E of the board
+        let me = Move::new(6, 6, word(ab, "ME"), Direction::Vertical);
+        assert_eq!(board.formed_words(&me), vec![word(ab, "ME")]);
+        assert_eq!(board.score(&me, &tiles(ab, "M")), 3);
+
+        // IT below HE makes HI and ET, the T is on a double letter counted
+        // in both words
+        let it = Move::new(5, 8, word(ab, "IT"), Direction::Horizontal);
+        assert_eq!(board.formed_words(&it), vec![word(ab, "IT"), word(ab, "HI"),
+            word(ab, "ET")]);
+        assert_eq!(board.score(&it, &tiles(ab, "IT")), 3 + 2 + 3);
+        let breakdown = board.score_breakdown(&it, &tiles(ab, "IT"));
+        assert_eq!(breakdown.words.iter().map(|w| w.1).collect::<Vec<u32>>(), vec![3, 2, 3]);
+        assert_eq!(breakdown.bingo, 0);
+
+        // The whole hand on a triple word and a double letter
+        let bingo = Move::new(0, 0, word(ab, "ABCDEFG"), Direction::Horizontal);
+        assert_eq!(board.score(&bingo, &tiles(ab, "ABCDEFG")), 8 * 3 + 50);
+        assert_eq!(board.score_breakdown(&bingo, &tiles(ab, "ABCDEFG")).bingo, 50);
+    }
+
+    #[test]
+    fn placed_moves() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let letter = |label : &str| ab.letter(label).unwrap();
+        let mut board = Board::new();
+        board.add_move(Move::new(5, 7, word(ab, "HELLO"), Direction::Horizontal), tiles(ab, "HELLO"));
+
+        // S after HELLO takes the whole word
+        let mv = board.placed_move(&[(10, 7, letter("S"))]).unwrap();
+        assert_eq!((mv.x(), mv.y(), mv.word()), (5, 7, &word(ab, "HELLOS")[..]));
+        // A single letter under the H goes down
+        let mv = board.placed_move(&[(5, 8, letter("A"))]).unwrap();
+        assert!(matches!(mv.direction(), Direction::Vertical));
+        assert_eq!((mv.x(), mv.y(), mv.word()), (5, 7, &word(ab, "HA")[..]));
+        // Letters around the E in any order
+        let mv = board.placed_move(&[(6, 8, letter("T")), (6, 6, letter("B"))]).unwrap();
+        assert_eq!((mv.x(), mv.y(), mv.word()), (6, 6, &word(ab, "BET")[..]));
+
+        assert!(board.placed_move(&[]).is_none());
+        // Taken spot, hole in the word, not in one line
+        assert!(board.placed_move(&[(6, 7, letter("A"))]).is_none());
+        assert!(board.placed_move(&[(6, 5, letter("A")), (6, 9, letter("A"))]).is_none());
+        assert!(board.placed_move(&[(1, 1, letter("A")), (2, 2, letter("A"))]).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Points lost for each minute, or part of it, a player goes over its time
+pub const OVERTIME_PENALTY : u32 = 10;
+/// Time of each player in a tournament game
+const TOURNAMENT_TIME : Duration = Duration::from_secs(25 * 60);
+
+/// Where the game reads the time
+///
+/// Tests can give their own to control how time goes by.
+pub trait ClockSource {
+    /// Get the time elapsed since a fixed point, like the creation of the source
+    fn now(&self) -> Duration;
+}
+
+/// The time of the computer
+pub struct SystemClock {
+    origin : Instant,
+}
+
+impl SystemClock {
+    /// Create a source starting now
+    pub fn new() -> SystemClock {
+        SystemClock {
+            origin : Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl ClockSource for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// How much time the players have
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeControl {
+    /// The time each player starts with
+    pub initial : Duration,
+    /// The time given back to a player after each of its turns
+    pub increment : Duration,
+}
+
+impl TimeControl {
+    /// Create a time control
+    ///
+    /// # Arguments
+    /// * `initial` - The time each player starts with.
+    /// * `increment` - The time added after each turn, known as Fischer increment.
+    pub fn new(initial : Duration, increment : Duration) -> TimeControl {
+        TimeControl {
+            initial,
+            increment,
+        }
+    }
+
+    /// 25 minutes per player, without increment
+    pub fn tournament() -> TimeControl {
+        TimeControl::new(TOURNAMENT_TIME, Duration::from_secs(0))
+    }
+}
+
+/// The clock of one player
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clock {
+    /// The time left before going over
+    left : Duration,
+    /// The time spent after the clock reached 0
+    overtime : Duration,
+    increment : Duration,
+}
+
+impl Clock {
+    /// Create a clock for a player
+    pub fn new(control : TimeControl) -> Clock {
+        Clock {
+            left : control.initial,
+            overtime : Duration::from_secs(0),
+            increment : control.increment,
+        }
+    }
+
+    /// Take `time` out of the clock, going into overtime when it's over
+    pub fn spend(&mut self, time : Duration) {
+        if time <= self.left {
+            self.left -= time;
+        }
+        else {
+            self.overtime += time - self.left;
+            self.left = Duration::from_secs(0);
+        }
+    }
+
+    /// Give the increment back, once a turn is over
+    ///
+    /// It pays back the overtime first.
+    pub fn add_increment(&mut self) {
+        if self.increment <= self.overtime {
+            self.overtime -= self.increment;
+        }
+        else {
+            self.left += self.increment - self.overtime;
+            self.overtime = Duration::from_secs(0);
+        }
+    }
+
+    /// Get the time left
+    pub fn left(&self) -> Duration {
+        self.left
+    }
+
+    /// Get the time spent after the clock reached 0
+    pub fn overtime(&self) -> Duration {
+        self.overtime
+    }
+
+    /// Get the points lost for the overtime
+    ///
+    /// Each minute started counts as a full one.
+    pub fn penalty(&self) -> u32 {
+        let minutes = self.overtime.as_millis().div_ceil(60_000);
+        minutes as u32 * OVERTIME_PENALTY
+    }
+}
+
+impl fmt::Display for Clock {
+    /// Write the time left as `MM:SS`, or the overtime as `-MM:SS`
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        let (sign, time) = if self.overtime > Duration::from_secs(0) {
+            ("-", self.overtime)
+        }
+        else {
+            ("", self.left)
+        };
+        let seconds = time.as_secs();
+        write!(f, "{}{:02}:{:02}", sign, seconds / 60, seconds % 60)
+    }
+}
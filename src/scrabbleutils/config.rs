@@ -1,13 +1,23 @@
+use std::fmt;
 use std::fs::File;
-use std::io::{prelude::*, BufReader};
-use super::Tile;
+use std::io::{self, prelude::*, BufReader};
+use super::{Tile, Alphabet, Letter};
+use super::hand::HAND_CAPACITY;
 
 /// This is also known as the joker
 pub const WILDCARD : char = '*';
+/// Starts a comment in a tileset file
+const COMMENT : char = '#';
+/// Header giving the number of tiles in a hand
+const RACK_HEADER : &str = "rack";
+/// Header giving the number of wildcards, worth 0 points
+const BLANKS_HEADER : &str = "blanks";
 
 /// Simple struct that stores information about how many times we add this tile
 /// in the tileset
 pub struct TileInfo {
+    /// How the letter is written
+    label : String,
     tile : Tile,
     occurences : u32,
 }
@@ -15,31 +25,89 @@ pub struct TileInfo {
 /// Stores all the TileInfo
 pub struct TileSet {
     infos : Vec<TileInfo>,
+    /// The letters of the tiles, wildcard excepted
+    alphabet : Alphabet,
+    /// The points of each letter, indexed by `Letter`
+    points : Vec<u8>,
+    /// How many tiles a hand holds
+    rack_size : usize,
+}
+
+/// Why a tileset could not be read
+#[derive(Debug)]
+pub enum TileSetError {
+    /// The file could not be read
+    Io(io::Error),
+    /// A line is malformed, positions start at 1
+    Syntax {
+        line : usize,
+        column : usize,
+        message : String,
+    },
+}
+
+impl fmt::Display for TileSetError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TileSetError::Io(e) => write!(f, "{}", e),
+            TileSetError::Syntax { line, column, message } =>
+                write!(f, "line {}, column {}: {}", line, column, message),
+        }
+    }
+}
+
+impl From<io::Error> for TileSetError {
+    fn from(e : io::Error) -> TileSetError {
+        TileSetError::Io(e)
+    }
+}
+
+/// Split a line into its words and their column, up to a comment
+fn tokens(line : &str) -> Vec<(usize, &str)> {
+    let mut tokens : Vec<(usize, &str)> = Vec::new();
+    let mut start : Option<(usize, usize)> = None;
+    let mut end = line.len();
+    for (column, (index, c)) in line.char_indices().enumerate() {
+        if c == COMMENT || c.is_whitespace() {
+            if let Some((column, begin)) = start.take() {
+                tokens.push((column + 1, &line[begin..index]));
+            }
+            if c == COMMENT {
+                end = index;
+                break;
+            }
+        } else if start.is_none() {
+            start = Some((column, index));
+        }
+    }
+    if let Some((column, begin)) = start {
+        tokens.push((column + 1, &line[begin..end]));
+    }
+    tokens
 }
 
 impl TileInfo {
     /// Create a TileInfo
     ///
     /// # Arguments
-    /// * `c` - The character on the tile.
+    /// * `label` - The letter on the tile, one or more characters.
     /// * `occurences` - How many of this letter must be present in the bag.
     /// * `score` - The score it gives.
-    pub fn new(c : char, occurences : u32, score : u8) -> TileInfo {
+    pub fn new(label : &str, occurences : u32, score : u8) -> TileInfo {
         // If this is a wildcard, set the flag
-        let wildcard = match c {
-            WILDCARD => true,
-            _ => false,
-        };
-        let tile = Tile::new(c, score, wildcard);
+        let wildcard = label.chars().eq(std::iter::once(WILDCARD));
+        // The letter is set once the TileSet knows all the letters
+        let tile = Tile::new(0, score, wildcard);
         TileInfo {
+            label : label.to_uppercase(),
             tile,
             occurences,
         }
     }
 
-    /// Get the letter of this TileInfo's Tile
-    pub fn c(&self) -> char {
-        return self.tile.letter();
+    /// Get the way the letter of this TileInfo's Tile is written
+    pub fn label(&self) -> &str {
+        &self.label
     }
 
     /// Get the occurences
@@ -62,60 +130,219 @@ impl TileSet {
     /// Create a TileSet from a vector of `TileInfo`
     ///
     /// Maybe someone need it, otherwise there is `from_file()`
+    ///
+    /// The letters are numbered in the order they come, the wildcard
+    /// excepted. A hand holds 7 tiles.
     pub fn from_vec(vec : Vec<TileInfo>) -> TileSet {
+        let mut infos = vec;
+        let mut labels : Vec<String> = Vec::new();
+        let mut points : Vec<u8> = Vec::new();
+        for info in infos.iter_mut() {
+            if info.tile.wildcard() {
+                continue;
+            }
+            let letter = match labels.iter().position(|l| *l == info.label) {
+                Some(index) => index,
+                None => {
+                    labels.push(info.label.clone());
+                    points.push(info.tile.points());
+                    labels.len() - 1
+                }
+            };
+            info.tile = Tile::new(letter as Letter, info.tile.points(), false);
+        }
         TileSet {
-            infos : vec,
+            infos,
+            alphabet : Alphabet::new(labels),
+            points,
+            rack_size : HAND_CAPACITY,
         }
     }
 
     /// Create a TileSet from a file
     ///
-    /// The file need to have a special format to be understood.
+    /// See `from_reader()` for the format of the file.
+    ///
+    /// # Errors
+    /// If the file can't be read or is malformed.
+    pub fn from_file(filename : &str) -> Result<TileSet, TileSetError> {
+        let file = File::open(filename)?;
+        TileSet::from_reader(BufReader::new(file))
+    }
+
+    /// Read a TileSet
+    ///
     /// It is read line by line, and each one describe a TileInfo.
     /// A TileInfo is described like so :
     /// <letter> <occurences> <score>
+    /// where the letter can be written with several characters, like `CH`.
+    ///
+    /// Blank lines are ignored, and a `#` starts a comment up to the end of
+    /// the line. Before the tiles, these headers can be given :
+    /// * `rack <size>` - How many tiles a hand holds, 7 by default.
+    /// * `blanks <count>` - How many wildcards are in the bag, instead of a
+    ///   `*` line.
     ///
     /// Have a look at the TileInfo constructor for more informations about these parameters.
-    pub fn from_file(filename : &str) -> TileSet {
+    ///
+    /// # Errors
+    /// If a line is malformed or a letter is given twice, with its position.
+    pub fn from_reader<R : BufRead>(reader : R) -> Result<TileSet, TileSetError> {
         let mut ts_vec : Vec<TileInfo> = Vec::new();
-        let file = File::open(filename).unwrap();
-        let reader = BufReader::new(file);
-        for line in reader.lines() {
-            let line = line.unwrap();
-            let data : Vec<&str> = line.split_whitespace().collect();
-            assert!(data.len() == 3, "not 3 elements on one line in tileset file");
-            let c : char = data[0].parse().expect("The first word of a line should be a char");
-            let occurences : u32 = data[1].parse().expect("The second word should be a number");
-            let score : u8 = data[2].parse().expect("The third word should be a number");
-            let ti = TileInfo::new(c, occurences, score);
-            ts_vec.push(ti);
+        // Where each label was given, to report duplicates
+        let mut seen : Vec<(String, usize)> = Vec::new();
+        let mut rack_size : Option<usize> = None;
+        let mut blanks : Option<(u32, usize)> = None;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let number = index + 1;
+            let error = |column : usize, message : String| TileSetError::Syntax {
+                line : number,
+                column,
+                message,
+            };
+            let data = tokens(&line);
+            match data.as_slice() {
+                [] => continue,
+                [(column, header), (value_column, value)]
+                        if *header == RACK_HEADER || *header == BLANKS_HEADER => {
+                    if !ts_vec.is_empty() {
+                        return Err(error(*column, format!("`{}` must come before the tiles", header)));
+                    }
+                    let value : u32 = value.parse().map_err(|_|
+                        error(*value_column, format!("`{}` is not a number", value)))?;
+                    if *header == RACK_HEADER {
+                        if rack_size.is_some() {
+                            return Err(error(*column, "the rack size is already given".to_string()));
+                        }
+                        if value == 0 {
+                            return Err(error(*value_column, "a rack holds at least one tile".to_string()));
+                        }
+                        rack_size = Some(value as usize);
+                    } else {
+                        if blanks.is_some() {
+                            return Err(error(*column, "the blank count is already given".to_string()));
+                        }
+                        blanks = Some((value, number));
+                    }
+                }
+                [(column, label), (occ_column, occurences), (score_column, score)] => {
+                    let label = label.to_uppercase();
+                    let wildcard = label.chars().eq(std::iter::once(WILDCARD));
+                    if let Some((_, line)) = seen.iter().find(|s| s.0 == label) {
+                        return Err(error(*column, format!("`{}` is already given on line {}", label, line)));
+                    }
+                    if let (Some((_, line)), true) = (blanks, wildcard) {
+                        return Err(error(*column, format!("the wildcards are already given on line {}", line)));
+                    }
+                    let occurences : u32 = occurences.parse().map_err(|_|
+                        error(*occ_column, format!("`{}` is not a number of tiles", occurences)))?;
+                    let score : u8 = score.parse().map_err(|_|
+                        error(*score_column, format!("`{}` is not a score", score)))?;
+                    if !wildcard && ts_vec.iter().filter(|i| !i.tile.wildcard()).count() > Letter::MAX as usize {
+                        return Err(error(*column, "there are too many letters".to_string()));
+                    }
+                    seen.push((label.clone(), number));
+                    ts_vec.push(TileInfo::new(&label, occurences, score));
+                }
+                _ => {
+                    return Err(error(data[0].0,
+                        "expected `<letter> <occurences> <score>`".to_string()));
+                }
+            }
         }
-        TileSet {
-            infos : ts_vec
+        if let Some((count, _)) = blanks {
+            ts_vec.push(TileInfo::new(&WILDCARD.to_string(), count, 0));
         }
+        let mut tileset = TileSet::from_vec(ts_vec);
+        tileset.rack_size = rack_size.unwrap_or(HAND_CAPACITY);
+        Ok(tileset)
+    }
+
+    /// Get the score of a letter, 0 if we don't know it
+    pub fn get_points(&self, letter : Letter) -> u8 {
+        self.points.get(letter as usize).copied().unwrap_or(0)
     }
 
-    /// Get the score of a letter
+    /// Get how many tiles a hand holds
+    pub fn rack_size(&self) -> usize {
+        self.rack_size
+    }
+
+    pub fn infos(&self) -> &Vec<TileInfo> {
+        return &self.infos;
+    }
+
+    /// Get the tiles of the set that are not among `seen`
     ///
-    /// It will look for the first tile with this score and get its score.
-    /// TODO : It's complexity is O(n)
-    pub fn get_points(&self, letter : char) -> u8 {
-        let mut it = self.infos.iter();
-        let pos = it.position(|e| e.tile.letter() == letter);
-        match pos {
-            Some(p) => {
-
-                return self.infos.get(p).unwrap().tile.points();
-            }
-            None => {
-                // If we don't know this letter we return 0
-                return 0;
+    /// A wildcard is told apart from the other tiles, whatever letter it
+    /// stands for. There are none of a letter when `seen` has too many.
+    pub fn unseen(&self, seen : &[Tile]) -> Vec<Tile> {
+        let mut unseen : Vec<Tile> = Vec::new();
+        for info in self.infos.iter() {
+            let tile = info.tile();
+            let used = seen.iter().filter(|t| t.wildcard() == tile.wildcard()
+                && (t.wildcard() || t.letter() == tile.letter())).count() as u32;
+            for _ in used..info.occurences() {
+                unseen.push(tile.clone());
             }
         }
+        unseen
     }
 
+    /// Get the letters of the tiles
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+}
 
-    pub fn infos(&self) -> &Vec<TileInfo> {
-        return &self.infos;
+#[cfg(test)]
+mod test {
+    use super::{TileSet, TileSetError};
+
+    #[test]
+    fn presets() {
+        for file in ["english", "french", "german", "italian", "spanish"].iter() {
+            let tileset = TileSet::from_file(&format!("{}_tileset.txt", file)).unwrap();
+            assert!(tileset.alphabet().len() >= 21);
+        }
+
+        let spanish = TileSet::from_file("spanish_tileset.txt").unwrap();
+        let alphabet = spanish.alphabet();
+        let ch = alphabet.letter("CH").unwrap();
+        assert_eq!(spanish.get_points(ch), 5);
+        assert_eq!(alphabet.encode("Chorro"), Some(vec![ch,
+            alphabet.letter("O").unwrap(), alphabet.letter("RR").unwrap(),
+            alphabet.letter("O").unwrap()]));
+    }
+
+    fn syntax_error(text : &str) -> (usize, usize) {
+        match TileSet::from_reader(text.as_bytes()) {
+            Err(TileSetError::Syntax { line, column, .. }) => (line, column),
+            _ => panic!("{:?} should not be read", text),
+        }
+    }
+
+    #[test]
+    fn extended_syntax() {
+        let text = "# A small set\nrack 5\nblanks 3\n\nA 2 1  # vowels\n  b 1 4\n";
+        let tileset = TileSet::from_reader(text.as_bytes()).unwrap();
+        assert_eq!(tileset.rack_size(), 5);
+        assert_eq!(tileset.alphabet().len(), 2);
+        assert_eq!(tileset.get_points(tileset.alphabet().letter("B").unwrap()), 4);
+        let wildcards = tileset.infos().iter().find(|i| i.tile().wildcard()).unwrap();
+        assert_eq!((wildcards.occurences(), wildcards.score()), (3, 0));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(syntax_error("A 1 1\nB 1"), (2, 1));
+        assert_eq!(syntax_error("A 1 1\n\nB  x 1"), (3, 4));
+        assert_eq!(syntax_error("A 1 1\nB 1 300"), (2, 5));
+        assert_eq!(syntax_error("A 1 1\n a 2 2"), (2, 2));
+        assert_eq!(syntax_error("blanks 2\n* 2 0"), (2, 1));
+        assert_eq!(syntax_error("A 1 1\nrack 7"), (2, 1));
+        assert_eq!(syntax_error("rack 0"), (1, 6));
+        assert!(matches!(TileSet::from_file("missing_tileset.txt"), Err(TileSetError::Io(_))));
     }
 }
@@ -1,150 +1,579 @@
-use super::gaddag::gaddag;
+use std::collections::HashMap;
+use std::convert::TryInto;
 use std::fs::File;
-use std::io::{prelude::*, BufReader};
+use std::io::{self, prelude::*, BufReader};
+use std::ops::Range;
 
-use std::cell::RefCell;
+use memmap2::Mmap;
 
-use std::collections::HashMap;
-use std::rc::{Rc, Weak};
-
-use std::str::Chars;
-
-/// A Node/Branch/Graph used by `Dico`.
-struct Node {
-    /// Reference to its parent
-    parent : Option<Weak<RefCell<Node>>>,
-    /// HashMap of its children
-    children : HashMap<char, NodeRef>,
-    /// Its letter
-    letter : Option<char>,
-    /// Whether its terminal
-    terminal : bool
+use super::{Alphabet, Letter};
+
+/// Lines starting with it are ignored in dictionnary files
+const COMMENT : char = '#';
+/// First word of a compiled dictionnary file
+const MAGIC : u32 = 0x4741_5744; // "DAWG" in little endian
+/// Version of the compiled dictionnary format
+const VERSION : u32 = 2;
+/// Number of words before the alphabet: magic, version, root, nodes, edges
+/// and the size of the alphabet
+const HEADER_LEN : usize = 6;
+/// Separates the letters of the alphabet in a compiled dictionnary
+const LABEL_SEPARATOR : char = '\n';
+/// Bit of a node entry telling the node ends a word
+const TERMINAL : u32 = 1 << 31;
+
+/// Where the compiled graph lives
+enum Storage {
+    /// Built in memory
+    Owned(Vec<u8>),
+    /// Mapped from a compiled dictionnary file
+    Mapped(Mmap),
 }
 
-/// A shortcut.
-struct NodeRef (Rc<RefCell<Node>>);
+impl Storage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Storage::Owned(bytes) => bytes,
+            Storage::Mapped(map) => map,
+        }
+    }
+}
 
-/// An interface to the graph of `Node`.
+/// The dictionnary, stored as a DAWG (a trie whose common suffixes are
+/// shared).
+///
+/// Words are made of the `Letter`s of an alphabet, the one of the `TileSet`
+/// of the game.
+///
+/// The graph is a flat array of little endian `u32`, which is also the format
+/// of the compiled dictionnary files:
+/// * A header: magic, version, root node, number of nodes, number of edges,
+///   number of words the alphabet takes.
+/// * The alphabet: its letters as UTF-8, separated by new lines and padded
+///   with zeros.
+/// * One entry per node, plus a last one: the index of the node's first edge,
+///   with the `TERMINAL` bit set if the node ends a word. The edges of node
+///   `n` go from its entry to the entry of `n + 1`.
+/// * Two words per edge: the letter and the node it leads to. The edges of a
+///   node are sorted by letter.
 pub struct Dico {
-    /// The only `Node` with no parent and no letter.
-    first_node : NodeRef,
+    storage : Storage,
+    root : u32,
+    node_count : u32,
+    /// Index of the first node entry
+    nodes : usize,
+    /// The letters the words are made of
+    alphabet : Alphabet,
+    /// Optional definitions of the words
+    definitions : HashMap<String, String>,
 }
 
 impl Dico {
+    /// Create an empty dictionnary
+    pub fn new(alphabet : &Alphabet) -> Dico {
+        DicoBuilder::new(alphabet).build()
+    }
+
     /// Generate a new dictionnary from `filename`
     ///
-    /// Panic:
-    /// * The file cannot be opened (Maybe it does not exist)
-    pub fn new(filename : &str) -> Dico {
-        let file = File::open(filename);
-        let reader = BufReader::new(file.unwrap());
-
-        let first_node = NodeRef::new(None, None, false);
-        let dico = Dico {
-            first_node : (first_node),
-        };
+    /// See `DicoBuilder::add_reader()` for the format of the file.
+    ///
+    /// # Errors
+    /// If the file can't be opened or read.
+    pub fn from_file(filename : &str, alphabet : &Alphabet) -> io::Result<Dico> {
+        let mut builder = DicoBuilder::new(alphabet);
+        builder.add_file(filename)?;
+        Ok(builder.build())
+    }
 
-        for line in reader.lines() {
-            dico.add_word(line.unwrap().as_str());
+    /// Generate a new dictionnary from several files
+    ///
+    /// The words of all the files are merged, which is handy to add house
+    /// words on top of a base list.
+    ///
+    /// # Errors
+    /// If one of the files can't be opened or read.
+    pub fn from_files(filenames : &[&str], alphabet : &Alphabet) -> io::Result<Dico> {
+        let mut builder = DicoBuilder::new(alphabet);
+        for filename in filenames {
+            builder.add_file(filename)?;
         }
-        dico
+        Ok(builder.build())
+    }
+
+    /// Generate a new dictionnary from a list of words
+    ///
+    /// Words are normalized the same way as the lines of a file.
+    pub fn from_words<I, S>(words : I, alphabet : &Alphabet) -> Dico
+            where I : IntoIterator<Item = S>, S : AsRef<str> {
+        let mut builder = DicoBuilder::new(alphabet);
+        builder.add_words(words);
+        builder.build()
+    }
+
+    /// Load a dictionnary compiled with `save()`
+    ///
+    /// The file is memory mapped, so it is available right away and only the
+    /// parts being used are read from the disk. It must not be changed or
+    /// truncated while the dictionnary is alive.
+    ///
+    /// # Errors
+    /// If the file can't be opened or is not a valid compiled dictionnary.
+    pub fn load(filename : &str) -> io::Result<Dico> {
+        let file = File::open(filename).map_err(|e| io::Error::new(e.kind(),
+            format!("{}: {}", filename, e)))?;
+        // SAFETY: The caller must not change the file while it is mapped.
+        // Truncating it makes the next access to the missing pages crash the
+        // process with SIGBUS, bounds checks can't prevent that.
+        let map = unsafe { Mmap::map(&file)? };
+        Dico::from_storage(Storage::Mapped(map))
+    }
+
+    /// Write the compiled dictionnary to `filename`, see `load()`
+    ///
+    /// # Errors
+    /// If the file can't be written.
+    pub fn save(&self, filename : &str) -> io::Result<()> {
+        File::create(filename)?.write_all(self.storage.bytes())
     }
 
     /// Tells if the word is present in the dico.
-    pub fn exists(&self, word : &str) -> bool {
-        self.first_node.exists(word.chars())
+    pub fn exists(&self, word : &[Letter]) -> bool {
+        match self.walk(self.root(), word) {
+            None => false,
+            Some(node) => self.is_terminal(node),
+        }
     }
 
-    /// Add `word` to the dico
-    fn add_word(&self, word : &str) {
-        self.first_node.add_nexts(word.chars());
+    /// Tells if the written word is present in the dico.
+    pub fn exists_word(&self, word : &str) -> bool {
+        self.alphabet.encode(word).is_some_and(|word| self.exists(&word))
+    }
+
+    /// Tells if at least one word of the dico starts with `prefix`.
+    pub fn is_prefix(&self, prefix : &[Letter]) -> bool {
+        self.walk(self.root(), prefix).is_some()
     }
-}
 
-impl Node {
-    /// Create a new node with an optional reference to its parent.
+    /// Get the letters the words are made of
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Get the definition of `word`, if definitions were loaded
+    pub fn definition(&self, word : &str) -> Option<&str> {
+        self.definitions.get(&word.to_uppercase()).map(|d| d.as_str())
+    }
+
+    /// Load the definitions of `filename`
+    ///
+    /// See `add_definitions()` for the format of the file.
+    ///
+    /// # Errors
+    /// If the file can't be opened or read, or a line is malformed.
+    pub fn load_definitions(&mut self, filename : &str) -> io::Result<()> {
+        let file = File::open(filename).map_err(|e| io::Error::new(e.kind(),
+            format!("{}: {}", filename, e)))?;
+        self.add_definitions(BufReader::new(file)).map_err(|e|
+            io::Error::new(e.kind(), format!("{}: {}", filename, e)))
+    }
+
+    /// Add the definitions read from `reader`
+    ///
+    /// There is one definition per line: the word, a tab, then the
+    /// definition. Blank lines and lines starting with `#` are ignored.
+    /// A word defined on several lines gets all the definitions.
+    ///
+    /// # Errors
+    /// If the reader fails or a line has no tab.
+    pub fn add_definitions<R : BufRead>(&mut self, reader : R) -> io::Result<()> {
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(COMMENT) {
+                continue;
+            }
+            let (word, definition) = match line.find('\t') {
+                Some(index) => (&line[..index], line[index + 1..].trim()),
+                None => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                    format!("line {}: the word and its definition should be separated by a tab",
+                        number + 1))),
+            };
+            self.definitions.entry(word.trim().to_uppercase())
+                .and_modify(|d| { d.push_str("; "); d.push_str(definition); })
+                .or_insert_with(|| definition.to_string());
+        }
+        Ok(())
+    }
+
+    /// Get the number of nodes in the graph
+    pub fn node_count(&self) -> u32 {
+        self.node_count
+    }
+
+    /// Check a compiled graph and wrap it in a `Dico`
     ///
-    /// # Arguments
-    /// * `letter` - The letter of the node.
-    /// * `parent` - A reference to its parent NodeRef
-    /// * `terminal` - whether this node is terminal or not
-    fn new(letter : Option<char>, parent : Option<NodeRef>, terminal: bool) -> Node {
-        let weak_parent : Option<Weak<RefCell<Node>>>;
-        if let Some(x) = parent {
-            weak_parent = Some(Rc::downgrade(&x.0));
+    /// Everything an access relies on is checked here, so that a corrupted
+    /// file gives an error instead of a panic later.
+    fn from_storage(storage : Storage) -> io::Result<Dico> {
+        let invalid = |msg : &str| io::Error::new(io::ErrorKind::InvalidData,
+            format!("invalid compiled dictionnary: {}", msg));
+        let bytes = storage.bytes();
+        if !bytes.len().is_multiple_of(4) || bytes.len() < HEADER_LEN * 4 {
+            return Err(invalid("truncated header"));
         }
-        else {
-            weak_parent = None;
+        let word = |i : usize| read_u32(bytes, i);
+        if word(0) != MAGIC {
+            return Err(invalid("bad magic number"));
         }
-        Node {
-            parent : weak_parent,
-            children : HashMap::new(),
-            letter,
-            terminal,
+        if word(1) != VERSION {
+            return Err(invalid("unsupported version"));
+        }
+        let (root, node_count, edge_count) = (word(2), word(3), word(4));
+        let alphabet_len = word(5) as usize;
+        let expected = HEADER_LEN as u64 + alphabet_len as u64 +
+            node_count as u64 + 1 + 2 * edge_count as u64;
+        if bytes.len() as u64 != expected * 4 || root >= node_count {
+            return Err(invalid("sizes don't match"));
+        }
+
+        let labels = &bytes[HEADER_LEN * 4..(HEADER_LEN + alphabet_len) * 4];
+        let labels = std::str::from_utf8(labels)
+            .map_err(|_| invalid("bad alphabet"))?
+            .trim_end_matches('\0');
+        let labels : Vec<String> = match labels.is_empty() {
+            true => Vec::new(),
+            false => labels.split(LABEL_SEPARATOR).map(|l| l.to_string()).collect(),
+        };
+        if labels.len() > Letter::MAX as usize + 1 {
+            return Err(invalid("bad alphabet"));
+        }
+        let alphabet = Alphabet::new(labels);
+
+        let nodes = HEADER_LEN + alphabet_len;
+        let edges = nodes + node_count as usize + 1;
+        let mut previous = 0;
+        for node in 0..=node_count as usize {
+            let first = word(nodes + node) & !TERMINAL;
+            if first < previous || first > edge_count {
+                return Err(invalid("bad edge index"));
+            }
+            previous = first;
+        }
+        if previous != edge_count {
+            return Err(invalid("bad edge index"));
+        }
+        for edge in 0..edge_count as usize {
+            if word(edges + 2 * edge) as usize >= alphabet.len() ||
+                    word(edges + 2 * edge + 1) >= node_count {
+                return Err(invalid("bad edge"));
+            }
+        }
+
+        Ok(Dico {
+            storage,
+            root,
+            node_count,
+            nodes,
+            alphabet,
+            definitions : HashMap::new(),
+        })
+    }
+
+    /// Get the `i`th word of the graph
+    fn word(&self, i : usize) -> u32 {
+        read_u32(self.storage.bytes(), i)
+    }
+
+    /// Get the node every word starts from
+    pub(super) fn root(&self) -> u32 {
+        self.root
+    }
+
+    /// Whether a word ends on `node`
+    pub(super) fn is_terminal(&self, node : u32) -> bool {
+        self.word(self.nodes + node as usize) & TERMINAL != 0
+    }
+
+    /// Get the indexes of the edges leaving `node`, see `edge()`
+    pub(super) fn edges(&self, node : u32) -> Range<usize> {
+        let first = self.word(self.nodes + node as usize) & !TERMINAL;
+        let last = self.word(self.nodes + node as usize + 1) & !TERMINAL;
+        first as usize..last as usize
+    }
+
+    /// Get the letter and the node an edge leads to
+    pub(super) fn edge(&self, edge : usize) -> (Letter, u32) {
+        let edges = self.nodes + self.node_count as usize + 1;
+        // Letters were checked when the graph was loaded
+        (self.word(edges + 2 * edge) as Letter, self.word(edges + 2 * edge + 1))
+    }
+
+    /// Get the letters and the nodes following `node`, sorted by letter
+    pub(super) fn children(&self, node : u32) -> impl Iterator<Item = (Letter, u32)> + '_ {
+        self.edges(node).map(move |edge| self.edge(edge))
+    }
+
+    /// Get the node following `node` with `letter`
+    fn child(&self, node : u32, letter : Letter) -> Option<u32> {
+        self.children(node).find(|(c, _)| *c == letter).map(|(_, next)| next)
+    }
+
+    /// Follow `word` from `node`
+    pub(super) fn walk(&self, node : u32, word : &[Letter]) -> Option<u32> {
+        let mut node = node;
+        for c in word {
+            node = self.child(node, *c)?;
         }
+        Some(node)
     }
+}
+
+/// Read the `i`th little endian `u32` of `bytes`
+fn read_u32(bytes : &[u8], i : usize) -> u32 {
+    u32::from_le_bytes(bytes[4 * i..4 * i + 4].try_into().unwrap())
+}
+
+/// A node of the trie built by `DicoBuilder`
+struct TrieNode {
+    terminal : bool,
+    /// The letters following this node and their node, sorted by letter
+    children : Vec<(Letter, usize)>,
+}
 
+/// Gather words and compile them into a `Dico`
+pub struct DicoBuilder {
+    /// The trie of all the words, the first node is the root
+    nodes : Vec<TrieNode>,
+    alphabet : Alphabet,
+    /// How many words were not made of the alphabet's letters
+    skipped : usize,
 }
 
-impl NodeRef {
-    /// Create a new NodeRef
+impl DicoBuilder {
+    /// Create a builder with no word
     ///
-    /// # Arguments
-    /// Same as Node's
-    fn new(letter : Option<char>, parent : Option<NodeRef>,
-            terminal : bool) -> NodeRef {
-        return NodeRef {
-            0 : Rc::new(RefCell::new(Node::new(letter, parent, terminal))),
+    /// # Argument
+    /// * `alphabet` - The letters the words are made of
+    pub fn new(alphabet : &Alphabet) -> DicoBuilder {
+        DicoBuilder {
+            nodes : vec![TrieNode { terminal : false, children : Vec::new() }],
+            alphabet : alphabet.clone(),
+            skipped : 0,
         }
     }
 
-    /// Get copy
+    /// Get the number of words skipped because they are not made of the
+    /// alphabet's letters
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Add the words of `filename`
     ///
-    /// This is a special copy, NodeRef uses Rc<RefCell<Node>>, so the data
-    /// in the copy points to the same address and is mutable. Use with care.
-    fn copy(&self) -> Self {
-        let cloned = Rc::clone(&self.0);
-        return Self {
-            0 : cloned,
-        };
+    /// # Errors
+    /// If the file can't be opened or read.
+    pub fn add_file(&mut self, filename : &str) -> io::Result<()> {
+        let file = File::open(filename).map_err(|e| io::Error::new(e.kind(),
+            format!("{}: {}", filename, e)))?;
+        self.add_reader(BufReader::new(file))
     }
 
-    /// Add a child to the children list
+    /// Add the words read from `reader`
     ///
-    /// It will add a child with the first element of `chars` if needed and
-    /// pass the remaining of `chars` of the appropriate child.
+    /// There is one word per line. Surrounding whitespaces (including the
+    /// `\r` of windows files) are ignored, as are blank lines and lines
+    /// starting with `#`. Words are uppercased.
     ///
-    /// # Argument
-    /// * `chars` - A char iterator
-    fn add_nexts(&self, mut chars: Chars) {
-        let first_letter = chars.next();
-        if let None = first_letter {
-            self.0.borrow_mut().terminal = true;
+    /// # Errors
+    /// If the reader fails or gives something that is not UTF-8.
+    pub fn add_reader<R : BufRead>(&mut self, reader : R) -> io::Result<()> {
+        for line in reader.lines() {
+            self.add_word(line?.as_str());
+        }
+        Ok(())
+    }
+
+    /// Add a list of words
+    ///
+    /// Words are normalized the same way as the lines of a file.
+    pub fn add_words<I, S>(&mut self, words : I)
+            where I : IntoIterator<Item = S>, S : AsRef<str> {
+        for word in words {
+            self.add_word(word.as_ref());
+        }
+    }
+
+    /// Add `word`
+    ///
+    /// It is trimmed and split into letters with `Alphabet::encode()`, it is
+    /// skipped if it is empty, a comment, or not made of the alphabet's
+    /// letters.
+    pub fn add_word(&mut self, word : &str) {
+        let word = word.trim();
+        if word.is_empty() || word.starts_with(COMMENT) {
             return;
         }
-        let first_letter = first_letter.unwrap();
-        let mut current_node = self.0.borrow_mut();
-        let next_node = current_node
-                .children.entry(first_letter)
-                .or_insert(NodeRef::new(Some(first_letter), Some(self.copy()), false));
-        next_node.add_nexts(chars);
+        let letters = match self.alphabet.encode(word) {
+            Some(letters) => letters,
+            None => {
+                self.skipped += 1;
+                return;
+            }
+        };
+        let mut node = 0;
+        for c in letters {
+            node = match self.nodes[node].children.binary_search_by_key(&c, |e| e.0) {
+                Ok(index) => self.nodes[node].children[index].1,
+                Err(index) => {
+                    let next = self.nodes.len();
+                    self.nodes.push(TrieNode { terminal : false, children : Vec::new() });
+                    self.nodes[node].children.insert(index, (c, next));
+                    next
+                }
+            };
+        }
+        self.nodes[node].terminal = true;
     }
 
-    /// Whether there is a terminal node for this word
+    /// Compile the words into a `Dico`
     ///
-    /// # Argument
-    /// * `chars` - A char iterator
-    fn exists(&self, mut word : Chars) -> bool {
-        let next = word.next();
-        if let None = next {
-            return self.0.borrow().terminal;
-        }
-        let next = next.unwrap();
-        let current_node = self.0.borrow();
-        let child : Option<&NodeRef> = current_node.children.get(&next);
-        match child {
-            None => false,
-            Some(x) => return x.exists(word)
+    /// Nodes with the same ending words are merged into one.
+    pub fn build(self) -> Dico {
+        // A child is always created after its parent, so going backward
+        // handles the children first.
+        let mut ids : Vec<u32> = vec![0; self.nodes.len()];
+        let mut register : HashMap<(bool, Vec<(Letter, u32)>), u32> = HashMap::new();
+        let mut compiled : Vec<(bool, Vec<(Letter, u32)>)> = Vec::new();
+        for index in (0..self.nodes.len()).rev() {
+            let node = &self.nodes[index];
+            let key = (node.terminal, node.children.iter()
+                .map(|(c, next)| (*c, ids[*next])).collect());
+            ids[index] = *register.entry(key).or_insert_with_key(|key| {
+                compiled.push(key.clone());
+                (compiled.len() - 1) as u32
+            });
+        }
+
+        let mut labels : Vec<u8> = (0..self.alphabet.len())
+            .map(|l| self.alphabet.label(l as Letter))
+            .collect::<Vec<&str>>()
+            .join(&LABEL_SEPARATOR.to_string())
+            .into_bytes();
+        labels.resize(labels.len().div_ceil(4) * 4, 0);
+
+        let edge_count : usize = compiled.iter().map(|node| node.1.len()).sum();
+        let mut words : Vec<u32> = Vec::with_capacity(
+            HEADER_LEN + compiled.len() + 1 + 2 * edge_count);
+        words.extend_from_slice(&[MAGIC, VERSION, ids[0], compiled.len() as u32,
+            edge_count as u32, labels.len() as u32 / 4]);
+        words.extend(labels.chunks(4).map(|w| read_u32(w, 0)));
+        let mut first = 0;
+        for (terminal, children) in compiled.iter() {
+            words.push(first | if *terminal { TERMINAL } else { 0 });
+            first += children.len() as u32;
         }
+        words.push(first);
+        for (_, children) in compiled.iter() {
+            for (c, next) in children {
+                words.push(*c as u32);
+                words.push(*next);
+            }
+        }
+
+        let bytes = words.iter().flat_map(|w| w.to_le_bytes().to_vec()).collect();
+        Dico::from_storage(Storage::Owned(bytes))
+            .expect("a freshly compiled dictionnary is always valid")
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{Dico, DicoBuilder};
+    use super::super::{Alphabet, TileSet};
+
+    fn english() -> Alphabet {
+        TileSet::from_file("english_tileset.txt").unwrap().alphabet().clone()
+    }
+
+    #[test]
+    fn normalizes_lines() {
+        let mut builder = DicoBuilder::new(&english());
+        let file = "# Some comment\r\nhello\r\n\n  World \nnaïve\n\n";
+        builder.add_reader(file.as_bytes()).unwrap();
+        assert_eq!(builder.skipped(), 0);
+        let dico = builder.build();
+
+        assert!(dico.exists_word("HELLO"));
+        assert!(dico.exists_word("world"));
+        assert!(dico.exists_word("NAIVE"));
+        assert!(!dico.exists_word("# SOME COMMENT"));
+        assert!(!dico.exists_word(""));
+        assert!(dico.is_prefix(&english().encode("WOR").unwrap()));
+        assert!(!dico.is_prefix(&english().encode("WOO").unwrap()));
+    }
+
+    #[test]
+    fn merges_lists() {
+        let mut builder = DicoBuilder::new(&english());
+        builder.add_file("dico.txt").unwrap();
+        builder.add_words(vec!["zyzzyva", "QI", "Ωmega"]);
+        assert_eq!(builder.skipped(), 1);
+        let dico = builder.build();
+
+        assert!(dico.exists_word("MARIO"));
+        assert!(dico.exists_word("ZYZZYVA"));
+        assert!(dico.exists_word("QI"));
+        assert!(Dico::from_file("no_such_file.txt", &english()).is_err());
+    }
+
+    #[test]
+    fn shares_suffixes() {
+        let dico = Dico::from_words(vec!["CATS", "BATS", "RATS"], &english());
+        // Root, the first letters share the "ATS" nodes, and the end
+        assert_eq!(dico.node_count(), 5);
+        assert!(dico.exists_word("BATS"));
+        assert!(!dico.exists_word("BAT"));
+    }
+
+    #[test]
+    fn can_be_shared_between_threads() {
+        fn assert_send_sync<T : Send + Sync>() {}
+        assert_send_sync::<Dico>();
+    }
+
+    #[test]
+    fn definitions() {
+        let mut dico = Dico::from_file("dico.txt", &english()).unwrap();
+        assert_eq!(dico.definition("HELLO"), None);
+
+        let file = "# word\tdefinition\nhello\tA greeting\r\n\nHELLO\tA call\n";
+        dico.add_definitions(file.as_bytes()).unwrap();
+        assert_eq!(dico.definition("hello"), Some("A greeting; A call"));
+        assert!(dico.add_definitions("WORLD has no tab".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let spanish = Alphabet::new(vec!["A".to_string(), "CH".to_string(),
+            "O".to_string(), "Ñ".to_string()]);
+        // Tests run at the same time must not share the file
+        let name = format!("scrabble-rs-dico-test-{}.dawg", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        let path = path.to_str().unwrap();
+        Dico::from_words(vec!["CHOCHA", "AÑO"], &spanish).save(path).unwrap();
+
+        let dico = Dico::load(path).unwrap();
+        assert_eq!(dico.alphabet(), &spanish);
+        assert!(dico.exists_word("chocha"));
+        assert!(dico.exists_word("AÑO"));
+        assert!(!dico.exists_word("ANO"));
+        // The file can't change while it is mapped
+        drop(dico);
+
+        std::fs::write(path, b"not a dictionnary").unwrap();
+        assert!(Dico::load(path).is_err());
+        std::fs::remove_file(path).unwrap();
     }
 }
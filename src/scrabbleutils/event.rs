@@ -0,0 +1,69 @@
+use super::{Move, ScoreBreakdown, Tile};
+
+/// Where a player stands at the end of a game
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    /// The index of the player, in the order they play
+    pub player : usize,
+    pub name : String,
+    /// The final score, penalties taken out
+    pub score : u32,
+    /// The tiles left in the hand
+    pub rack : Vec<Tile>,
+    /// The points won for the tiles left to the others by going out, or lost
+    /// for the tiles left in the hand
+    pub tiles : i32,
+    /// The points lost for going over time
+    pub penalty : u32,
+}
+
+/// Something that happened in a game
+///
+/// Players are given by their index, in the order they play.
+#[derive(Clone)]
+pub enum Event {
+    /// The game starts, with the names of the players
+    GameStart {
+        players : Vec<String>,
+    },
+    /// A player is asked to play, with its hand filled up
+    ///
+    /// The hand is not shown to the other players.
+    TurnStart {
+        player : usize,
+        round : u32,
+        rack : Vec<Tile>,
+    },
+    /// A move was added to the board
+    Placed {
+        player : usize,
+        mv : Move,
+        breakdown : ScoreBreakdown,
+    },
+    /// A player exchanged some of its tiles
+    Exchanged {
+        player : usize,
+        count : usize,
+    },
+    Passed {
+        player : usize,
+    },
+    /// The dictionnary refused the word of a move, the player plays again
+    Challenged {
+        player : usize,
+        word : String,
+    },
+    /// The game is over, the best player first
+    GameEnd {
+        standings : Vec<Standing>,
+    },
+}
+
+/// Gets told about everything happening in a game
+///
+/// Spectator interfaces, loggers or bots can implement it and be given to
+/// `Game::add_observer()`.
+pub trait Observer {
+    /// Called for each event, in the order they happen
+    fn notify(&self, event : &Event);
+}
@@ -0,0 +1,917 @@
+use super::{Board, Dico, TileBag, TileSet, Player, PlayerTrait, Hand, Action, Move,
+    Tile, ScoreBreakdown, Position};
+use super::clock::{Clock, ClockSource, SystemClock, TimeControl};
+use super::event::{Event, Observer, Standing};
+use std::cmp::Reverse;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The game ends after this many rounds in a row without a word placed
+const SCORELESS_ROUNDS : usize = 3;
+/// How many times the players tied for the first turn draw again, before
+/// the first of them starts anyway
+const FIRST_DRAWS : usize = 20;
+
+/// Why a move was refused, the player is asked to play again
+#[derive(Debug, PartialEq)]
+pub enum MoveError {
+    /// The word is not in the dictionnary
+    UnknownWord(String),
+    /// The word does not fit there on the board
+    CantPlace(String),
+    /// The hand does not have the letters to make the word
+    MissingLetters(String),
+    /// The word is already on the board
+    NothingPlaced(String),
+    /// Letters of the board are right before or after the word, the word
+    /// really made is longer
+    Extended(String),
+    /// The word does not touch the tiles already on the board
+    NotConnected(String),
+    /// The first word does not cover the center of the board
+    OffCenter(String),
+    /// The tiles to exchange are not all in the hand, or there are none
+    NotInHand,
+    /// The bag has less tiles than a hand, exchanging is not allowed
+    BagTooSmall,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveError::UnknownWord(word) => write!(f, "{} is not in the dictionnary.", word),
+            MoveError::CantPlace(word) => write!(f, "{} can't be placed", word),
+            MoveError::MissingLetters(word) => write!(f, "{} can't be made with your letters", word),
+            MoveError::NothingPlaced(word) => write!(f, "{} already exists, you didn't change anything", word),
+            MoveError::Extended(word) => write!(f, "{} is part of a longer word on the board", word),
+            MoveError::NotConnected(word) => write!(f, "{} must touch the words on the board", word),
+            MoveError::OffCenter(word) => write!(f, "{} must cover the center of the board", word),
+            MoveError::NotInHand => write!(f, "you can only exchange tiles from your hand, at least one"),
+            MoveError::BagTooSmall => write!(f, "there are not enough tiles left in the bag to exchange"),
+        }
+    }
+}
+
+/// What a turn did
+pub enum Turn {
+    /// A move was added to the board, with how it scored
+    Placed(ScoreBreakdown),
+    /// This many tiles were exchanged
+    Exchanged(usize),
+    Passed,
+}
+
+/// What the players can see of a game
+struct State {
+    board : Board,
+    bag : TileBag,
+    dico : Arc<Dico>,
+    players : Vec<Player>,
+    /// The clock of each player, in the same order
+    clocks : Vec<Clock>,
+    /// The round number, starting at 1
+    round : u32,
+    /// What each turn did, in order
+    history : Vec<Event>,
+}
+
+impl State {
+    /// Check that `hand` can play `mv`
+    ///
+    /// The rules are the ones of the move generator: the first word covers
+    /// the center, the next ones touch the board, no letter of the board is
+    /// right before or after the word, and every word made across it is in
+    /// the dictionnary.
+    ///
+    /// # Return Value
+    /// The tiles the move takes from the hand.
+    fn check(&self, hand : &Hand, mv : &Move) -> Result<Vec<Tile>, MoveError> {
+        let word = self.dico.alphabet().decode(mv.word());
+        if !self.dico.exists(mv.word()) {
+            return Err(MoveError::UnknownWord(word));
+        }
+        if !self.board.can_place(mv) {
+            return Err(MoveError::CantPlace(word));
+        }
+        if self.board.is_extended(mv) {
+            return Err(MoveError::Extended(word));
+        }
+        if self.board.is_empty() {
+            if !self.board.covers_center(mv) {
+                return Err(MoveError::OffCenter(word));
+            }
+        }
+        else if !self.board.touches(mv) {
+            return Err(MoveError::NotConnected(word));
+        }
+        // The words made across are checked as well as the main one
+        for cross in self.board.formed_words(mv).iter().skip(1) {
+            if !self.dico.exists(cross) {
+                return Err(MoveError::UnknownWord(self.dico.alphabet().decode(cross)));
+            }
+        }
+        let letters = self.board.needed_letters(mv);
+        if !hand.contains(&letters) {
+            return Err(MoveError::MissingLetters(word));
+        }
+        match Hand::from_tiles(hand.get()).remove(&letters) {
+            Some(removed) => Ok(removed),
+            None => Err(MoveError::NothingPlaced(word)),
+        }
+    }
+}
+
+/// A game: the board, the bag, the players and their clocks
+///
+/// The players play in the order they were added.
+pub struct Game {
+    state : State,
+    /// What each player plays with, in the same order as the players
+    interfaces : Vec<Box<dyn PlayerTrait>>,
+    /// How many tiles a hand holds
+    rack_size : usize,
+    control : TimeControl,
+    source : Box<dyn ClockSource>,
+    /// The index of the player whose turn it is
+    current : usize,
+    observers : Vec<Rc<dyn Observer>>,
+    /// Whether the game start was told to the observers
+    started : bool,
+    /// Whether the start of the current turn was told to the observers
+    turn_started : bool,
+    /// The player who plays first, drawn when the game starts if None
+    first : Option<usize>,
+    /// How many turns in a row ended without a word placed
+    scoreless : usize,
+    /// The player who placed all its tiles with the bag empty
+    out : Option<usize>,
+}
+
+/// Get the players whose tile is the closest to A, a blank being the closest
+fn closest_to_a(draws : &[(usize, Tile)]) -> Vec<usize> {
+    let key = |tile : &Tile| (!tile.wildcard(), tile.letter());
+    let best = draws.iter().map(|(_, tile)| key(tile)).min();
+    draws.iter().filter(|(_, tile)| Some(key(tile)) == best).map(|(player, _)| *player).collect()
+}
+
+/// Get the points of the tiles of `hand`
+fn hand_points(hand : &Hand) -> u32 {
+    hand.get().iter().map(|tile| tile.points() as u32).sum()
+}
+
+impl Game {
+    /// Create a game without players
+    ///
+    /// The players have the tournament time, read from the computer clock.
+    ///
+    /// # Arguments
+    /// * `tileset` - The tiles put in the bag.
+    /// * `dico` - The dictionnary the moves are checked against.
+    pub fn new(tileset : &TileSet, dico : Arc<Dico>) -> Game {
+        Game {
+            state : State {
+                board : Board::new(),
+                bag : TileBag::new(tileset),
+                dico,
+                players : Vec::new(),
+                clocks : Vec::new(),
+                round : 1,
+                history : Vec::new(),
+            },
+            interfaces : Vec::new(),
+            rack_size : tileset.rack_size(),
+            control : TimeControl::tournament(),
+            source : Box::new(SystemClock::new()),
+            current : 0,
+            observers : Vec::new(),
+            started : false,
+            turn_started : false,
+            first : None,
+            scoreless : 0,
+            out : None,
+        }
+    }
+
+    /// Seat a player after the ones already there
+    pub fn add_player(&mut self, player : Box<dyn PlayerTrait>) {
+        let mut player_data = Player::new(player.name().to_string());
+        player_data.hand = Hand::with_capacity(self.rack_size);
+        self.state.players.push(player_data);
+        self.state.clocks.push(Clock::new(self.control));
+        self.interfaces.push(player);
+    }
+
+    /// Change the time of the players
+    ///
+    /// Their clocks are reset, so it should be done before the game starts.
+    pub fn set_time_control(&mut self, control : TimeControl) {
+        self.control = control;
+        for clock in self.state.clocks.iter_mut() {
+            *clock = Clock::new(control);
+        }
+    }
+
+    /// Shuffle the bag the same way for the same `seed`
+    ///
+    /// It should be done before the game starts.
+    pub fn set_seed(&mut self, seed : u64) {
+        self.state.bag.reseed(seed);
+    }
+
+    /// Play on another board, one with a different layout for example
+    ///
+    /// It should be done before the game starts.
+    pub fn set_board(&mut self, board : Board) {
+        self.state.board = board;
+    }
+
+    /// Start from a position set up by hand
+    ///
+    /// The players already seated get their racks, in order, and the bag
+    /// holds the tiles of the position. It should be done before the game
+    /// starts, after the players are seated.
+    pub fn set_position(&mut self, position : &Position, tileset : &TileSet) {
+        self.state.board = position.board().clone();
+        for (index, player_data) in self.state.players.iter_mut().enumerate() {
+            player_data.hand.set_tiles(position.rack(index).to_vec());
+        }
+        self.state.bag = TileBag::from_tiles(position.bag(tileset));
+        self.first = Some(0);
+    }
+
+    /// Let `player` play first, rather than drawing tiles for it
+    ///
+    /// It should be done before the game starts.
+    pub fn set_first(&mut self, player : usize) {
+        self.first = Some(player);
+    }
+
+    /// Change where the time is read from
+    pub fn set_clock_source(&mut self, source : Box<dyn ClockSource>) {
+        self.source = source;
+    }
+
+    /// Tell `observer` about everything happening from now on
+    pub fn add_observer(&mut self, observer : Rc<dyn Observer>) {
+        self.observers.push(observer);
+    }
+
+    /// Tell the observers about `event`
+    ///
+    /// The turns are kept in the history and told to the opponents of the
+    /// player.
+    fn emit(&mut self, event : Event) {
+        for observer in self.observers.iter() {
+            observer.notify(&event);
+        }
+        let player = match event {
+            Event::Placed { player, .. } | Event::Exchanged { player, .. }
+                | Event::Passed { player } => player,
+            _ => return,
+        };
+        self.state.history.push(event);
+        let event = self.state.history.last().unwrap();
+        for (index, interface) in self.interfaces.iter_mut().enumerate() {
+            if index != player {
+                interface.opponent_moved(&GameView { state : &self.state, player : index }, event);
+            }
+        }
+    }
+
+    /// Find who plays first: each player draws a tile and the one closest to
+    /// A starts, a blank beating every letter
+    ///
+    /// The players tied draw again. The tiles go back in the bag.
+    fn draw_first(&mut self) -> usize {
+        let mut players : Vec<usize> = (0..self.state.players.len()).collect();
+        for _ in 0..FIRST_DRAWS {
+            if players.len() < 2 || self.state.bag.len() < players.len() {
+                break;
+            }
+            let draws : Vec<(usize, Tile)> = players.iter()
+                .filter_map(|player| self.state.bag.pick().map(|tile| (*player, tile)))
+                .collect();
+            players = closest_to_a(&draws);
+            self.state.bag.put_back(draws.into_iter().map(|(_, tile)| tile).collect());
+        }
+        players.first().copied().unwrap_or(0)
+    }
+
+    /// Start the game, if it was not started yet
+    ///
+    /// The first player is drawn unless it was chosen, then the hands are
+    /// filled up in the order of play. It is done by the first `play_turn()`
+    /// otherwise.
+    pub fn start(&mut self) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        let first = match self.first {
+            Some(first) => first,
+            None => self.draw_first(),
+        };
+        self.first = Some(first);
+        self.current = first;
+        let count = self.state.players.len();
+        for seat in 0..count {
+            self.state.players[(first + seat) % count].hand.draw(&mut self.state.bag);
+        }
+        let players = self.state.players.iter().map(|p| p.name.clone()).collect();
+        self.emit(Event::GameStart { players });
+        for (index, interface) in self.interfaces.iter_mut().enumerate() {
+            interface.new_game(&GameView { state : &self.state, player : index });
+        }
+    }
+
+    /// Let the current player play
+    ///
+    /// The time it takes to decide is taken from its clock, and its hand is
+    /// filled up once its move is made.
+    ///
+    /// # Errors
+    /// If the move is not valid. The turn is not over, the same player must
+    /// play again.
+    pub fn play_turn(&mut self) -> Result<Turn, MoveError> {
+        self.start();
+        let index = self.current;
+        if !self.turn_started {
+            self.turn_started = true;
+            let rack = self.state.players[index].hand.get();
+            self.emit(Event::TurnStart { player : index, round : self.state.round, rack });
+        }
+        let start = self.source.now();
+        let view = GameView { state : &self.state, player : index };
+        let action = self.interfaces[index].play(&view, &self.state.players[index].hand);
+        let spent = self.source.now().saturating_sub(start);
+        self.state.clocks[index].spend(spent);
+
+        let turn = match action {
+            Action::Pass => {
+                self.emit(Event::Passed { player : index });
+                Turn::Passed
+            }
+            Action::Exchange(tiles) => self.exchange(index, tiles)?,
+            Action::Place(mv) => self.place(index, mv)?,
+        };
+        self.state.clocks[index].add_increment();
+        self.turn_started = false;
+        if let Turn::Placed(_) = turn {
+            self.scoreless = 0;
+            let hand = &mut self.state.players[index].hand;
+            hand.draw(&mut self.state.bag);
+            if hand.get().is_empty() {
+                self.out = Some(index);
+            }
+        }
+        else {
+            self.scoreless += 1;
+        }
+        self.current = (self.current + 1) % self.state.players.len();
+        if Some(self.current) == self.first {
+            self.state.round += 1;
+        }
+        Ok(turn)
+    }
+
+    /// Check a move and add it to the board
+    fn place(&mut self, index : usize, mv : Move) -> Result<Turn, MoveError> {
+        let removed = match self.state.check(&self.state.players[index].hand, &mv) {
+            Err(MoveError::UnknownWord(word)) => {
+                self.emit(Event::Challenged { player : index, word : word.clone() });
+                return Err(MoveError::UnknownWord(word));
+            }
+            result => result?,
+        };
+        let player_data = &mut self.state.players[index];
+        player_data.hand.remove_tiles(&removed);
+
+        let breakdown = self.state.board.score_breakdown(&mv, &removed);
+        let score = breakdown.total();
+        self.state.board.add_move(mv.clone(), removed);
+        player_data.score += score;
+        self.interfaces[index].move_score(score);
+        self.interfaces[index].total_score(player_data.score);
+        self.emit(Event::Placed { player : index, mv, breakdown : breakdown.clone() });
+        Ok(Turn::Placed(breakdown))
+    }
+
+    /// Swap tiles of a hand with new ones from the bag
+    fn exchange(&mut self, index : usize, tiles : Vec<Tile>) -> Result<Turn, MoveError> {
+        if self.state.bag.len() < self.rack_size {
+            return Err(MoveError::BagTooSmall);
+        }
+        let hand = &mut self.state.players[index].hand;
+        let given = match hand.remove_tiles(&tiles) {
+            Some(given) if !given.is_empty() => given,
+            _ => return Err(MoveError::NotInHand),
+        };
+        // The new tiles are drawn before the old ones go back in the bag
+        let count = given.len();
+        hand.draw(&mut self.state.bag);
+        self.state.bag.put_back(given);
+        self.emit(Event::Exchanged { player : index, count });
+        Ok(Turn::Exchanged(count))
+    }
+
+    /// Whether the game is over: a player placed all its tiles with the bag
+    /// empty, or nobody placed a word for some rounds
+    pub fn is_over(&self) -> bool {
+        self.out.is_some() || self.scoreless >= SCORELESS_ROUNDS * self.state.players.len()
+    }
+
+    /// Get the player who ended the game by placing all its tiles
+    pub fn out(&self) -> Option<usize> {
+        self.out
+    }
+
+    /// End the game by counting the tiles left and taking the overtime
+    /// penalties out of the scores
+    ///
+    /// Each player loses the points of the tiles left in its hand. If a
+    /// player went out, it wins the points lost by all the others.
+    ///
+    /// It must be called once, when the game is over.
+    ///
+    /// # Return Value
+    /// Where each player stands, the best first.
+    pub fn end(&mut self) -> Vec<Standing> {
+        let left : u32 = self.state.players.iter().map(|p| hand_points(&p.hand)).sum();
+        let mut standings : Vec<Standing> = Vec::with_capacity(self.state.players.len());
+        for (index, (player_data, clock)) in self.state.players.iter_mut()
+                .zip(self.state.clocks.iter()).enumerate() {
+            let tiles = match self.out {
+                Some(out) if out == index => left as i32,
+                _ => -(hand_points(&player_data.hand) as i32),
+            };
+            let penalty = clock.penalty();
+            if tiles != 0 || penalty > 0 {
+                player_data.score = (player_data.score as i32 + tiles).max(0) as u32;
+                player_data.score = player_data.score.saturating_sub(penalty);
+                self.interfaces[index].total_score(player_data.score);
+            }
+            standings.push(Standing {
+                player : index,
+                name : player_data.name.clone(),
+                score : player_data.score,
+                rack : player_data.hand.get(),
+                tiles,
+                penalty,
+            });
+        }
+        standings.sort_by_key(|s| Reverse(s.score));
+        self.emit(Event::GameEnd { standings : standings.clone() });
+        for interface in self.interfaces.iter_mut() {
+            interface.game_over(&standings);
+        }
+        standings
+    }
+
+    /// Get what `player` is allowed to know about the game
+    pub fn view(&self, player : usize) -> GameView<'_> {
+        GameView {
+            state : &self.state,
+            player,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.state.board
+    }
+
+    pub fn dico(&self) -> &Dico {
+        &self.state.dico
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.state.players
+    }
+
+    /// Get the clocks, in the order of the players
+    pub fn clocks(&self) -> &[Clock] {
+        &self.state.clocks
+    }
+
+    /// Get the index of the player whose turn it is
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Get the round number, starting at 1
+    pub fn round(&self) -> u32 {
+        self.state.round
+    }
+}
+
+/// What a player knows about a game
+///
+/// Players are given by their index, in the order they play. The hands of the
+/// opponents are hidden, only their size is known.
+pub struct GameView<'a> {
+    state : &'a State,
+    /// The player looking at the game
+    player : usize,
+}
+
+impl<'a> GameView<'a> {
+    pub fn board(&self) -> &'a Board {
+        &self.state.board
+    }
+
+    /// Get the dictionnary the moves are checked against
+    pub fn dico(&self) -> &'a Dico {
+        &self.state.dico
+    }
+
+    /// Get the index of the player looking at the game
+    pub fn me(&self) -> usize {
+        self.player
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.state.players.len()
+    }
+
+    pub fn name(&self, player : usize) -> &'a str {
+        &self.state.players[player].name
+    }
+
+    pub fn score(&self, player : usize) -> u32 {
+        self.state.players[player].score
+    }
+
+    /// Get the number of tiles in the hand of a player
+    pub fn rack_size(&self, player : usize) -> usize {
+        self.state.players[player].hand.get().len()
+    }
+
+    /// Get the number of tiles left in the bag
+    pub fn bag_len(&self) -> usize {
+        self.state.bag.len()
+    }
+
+    /// Get the time left to a player
+    ///
+    /// The time of the turn being played is not taken out yet.
+    pub fn time_left(&self, player : usize) -> Duration {
+        self.state.clocks[player].left()
+    }
+
+    /// Get the clock of a player
+    pub fn clock(&self, player : usize) -> &'a Clock {
+        &self.state.clocks[player]
+    }
+
+    /// Get the round number, starting at 1
+    pub fn round(&self) -> u32 {
+        self.state.round
+    }
+
+    /// Get what each turn did so far: moves placed, exchanges and passes
+    pub fn history(&self) -> &'a [Event] {
+        &self.state.history
+    }
+
+    /// Check a move of the player's hand and score it, without playing it
+    ///
+    /// # Return Value
+    /// The words the move makes and their score.
+    ///
+    /// # Errors
+    /// Why the game would refuse the move.
+    pub fn preview(&self, mv : &Move) -> Result<ScoreBreakdown, MoveError> {
+        let removed = self.state.check(&self.state.players[self.player].hand, mv)?;
+        Ok(self.state.board.score_breakdown(mv, &removed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Hand, Action, PlayerTrait, Dico, TileSet, Event, Observer, Standing,
+        Move, Direction, Board, Position, Tile};
+    use super::super::clock::{ClockSource, TimeControl};
+    use super::{Game, GameView, Turn, MoveError, closest_to_a};
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// A clock that only moves when told to
+    struct FakeClock(Rc<Cell<Duration>>);
+
+    impl ClockSource for FakeClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    /// A player taking some time to pass
+    struct Slow {
+        time : Rc<Cell<Duration>>,
+        thinking : Duration,
+    }
+
+    impl PlayerTrait for Slow {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn play(&mut self, _game : &GameView, _hand : &Hand) -> Action {
+            self.time.set(self.time.get() + self.thinking);
+            Action::Pass
+        }
+
+        fn move_score(&mut self, _score : u32) {
+        }
+
+        fn total_score(&mut self, _score : u32) {
+        }
+    }
+
+    #[test]
+    fn clocks() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let mut game = Game::new(&tileset, Arc::new(Dico::new(tileset.alphabet())));
+        let time = Rc::new(Cell::new(Duration::from_secs(0)));
+        game.set_clock_source(Box::new(FakeClock(Rc::clone(&time))));
+        for seconds in [90, 5].iter() {
+            game.add_player(Box::new(Slow {
+                time : Rc::clone(&time),
+                thinking : Duration::from_secs(*seconds),
+            }));
+        }
+        game.set_time_control(TimeControl::new(Duration::from_secs(60),
+            Duration::from_secs(10)));
+        game.set_first(0);
+
+        assert!(matches!(game.play_turn(), Ok(Turn::Passed)));
+        assert!(matches!(game.play_turn(), Ok(Turn::Passed)));
+        assert_eq!(game.round(), 2);
+        assert_eq!(game.clocks()[0].to_string(), "-00:20");
+        assert_eq!(game.clocks()[1].to_string(), "01:05");
+        assert_eq!(game.clocks()[0].penalty(), 10);
+
+        game.play_turn().ok();
+        assert_eq!(game.clocks()[0].overtime(), Duration::from_secs(100));
+        let penalties : Vec<u32> = game.end().iter().map(|s| s.penalty).collect();
+        assert_eq!(penalties, vec![20, 0]);
+    }
+
+    /// A player giving back its first two tiles
+    struct Exchanger;
+
+    impl PlayerTrait for Exchanger {
+        fn name(&self) -> &str {
+            "exchanger"
+        }
+
+        fn play(&mut self, _game : &GameView, hand : &Hand) -> Action {
+            Action::Exchange(hand.get()[..2].to_vec())
+        }
+
+        fn move_score(&mut self, _score : u32) {
+        }
+
+        fn total_score(&mut self, _score : u32) {
+        }
+    }
+
+    /// A player passing and writing down the hooks called
+    struct Watcher(Rc<RefCell<Vec<String>>>);
+
+    impl PlayerTrait for Watcher {
+        fn name(&self) -> &str {
+            "watcher"
+        }
+
+        fn play(&mut self, _game : &GameView, _hand : &Hand) -> Action {
+            Action::Pass
+        }
+
+        fn move_score(&mut self, _score : u32) {
+        }
+
+        fn total_score(&mut self, _score : u32) {
+        }
+
+        fn new_game(&mut self, game : &GameView) {
+            self.0.borrow_mut().push(format!("new game {}", game.me()));
+        }
+
+        fn opponent_moved(&mut self, game : &GameView, _event : &Event) {
+            self.0.borrow_mut().push(format!("opponent moved {}", game.history().len()));
+        }
+
+        fn game_over(&mut self, standings : &[Standing]) {
+            self.0.borrow_mut().push(format!("game over {}", standings.len()));
+        }
+    }
+
+    /// Writes down the events it is told about
+    struct Recorder(RefCell<Vec<String>>);
+
+    impl Observer for Recorder {
+        fn notify(&self, event : &Event) {
+            let line = match event {
+                Event::GameStart { players } => format!("start {}", players.join(" ")),
+                Event::TurnStart { player, round, rack } => format!("turn {} {} {}", player, round, rack.len()),
+                Event::Placed { player, .. } => format!("placed {}", player),
+                Event::Exchanged { player, count } => format!("exchanged {} {}", player, count),
+                Event::Passed { player } => format!("passed {}", player),
+                Event::Challenged { player, .. } => format!("challenged {}", player),
+                Event::GameEnd { standings } => format!("end {}", standings.len()),
+            };
+            self.0.borrow_mut().push(line);
+        }
+    }
+
+    #[test]
+    fn events() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let mut game = Game::new(&tileset, Arc::new(Dico::new(tileset.alphabet())));
+        let recorder = Rc::new(Recorder(RefCell::new(Vec::new())));
+        game.add_observer(recorder.clone());
+        let hooks = Rc::new(RefCell::new(Vec::new()));
+        game.add_player(Box::new(Exchanger));
+        game.add_player(Box::new(Watcher(Rc::clone(&hooks))));
+        game.set_first(0);
+
+        assert!(matches!(game.play_turn(), Ok(Turn::Exchanged(2))));
+        assert_eq!(game.players()[0].hand.get().len(), 7);
+        assert!(matches!(game.play_turn(), Ok(Turn::Passed)));
+        let view = game.view(1);
+        assert_eq!(view.history().len(), 2);
+        assert_eq!((view.rack_size(0), view.rack_size(1)), (7, 7));
+        assert_eq!(view.bag_len(), 100 - 14);
+        game.end();
+        assert_eq!(*recorder.0.borrow(), vec!["start exchanger watcher", "turn 0 1 7",
+            "exchanged 0 2", "turn 1 1 7", "passed 1", "end 2"]);
+        assert_eq!(*hooks.borrow(), vec!["new game 1", "opponent moved 1", "game over 2"]);
+    }
+
+    /// Plays the moves it was given, in order
+    struct Scripted(Vec<Move>);
+
+    impl PlayerTrait for Scripted {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn play(&mut self, _game : &GameView, _hand : &Hand) -> Action {
+            Action::Place(self.0.remove(0))
+        }
+
+        fn move_score(&mut self, _score : u32) {
+        }
+
+        fn total_score(&mut self, _score : u32) {
+        }
+    }
+
+    #[test]
+    fn unknown_words() {
+        let tileset = TileSet::from_reader("I 1 1\nT 1 1\n".as_bytes()).unwrap();
+        let ab = tileset.alphabet();
+        let mut game = Game::new(&tileset, Arc::new(Dico::from_words(vec!["IT"], ab)));
+        game.add_player(Box::new(Scripted(vec![
+            Move::new(7, 7, ab.encode("TI").unwrap(), Direction::Horizontal),
+            Move::new(7, 7, ab.encode("IT").unwrap(), Direction::Horizontal),
+        ])));
+
+        assert_eq!(game.play_turn().err(), Some(MoveError::UnknownWord("TI".to_string())));
+        assert!(matches!(game.play_turn(), Ok(Turn::Placed { .. })));
+    }
+
+    #[test]
+    fn preview() {
+        let tileset = TileSet::from_reader("A 8 1\n".as_bytes()).unwrap();
+        let alphabet = tileset.alphabet().clone();
+        let dico = Arc::new(Dico::from_words(vec!["AA"], &alphabet));
+        let mut game = Game::new(&tileset, dico);
+        let time = Rc::new(Cell::new(Duration::from_secs(0)));
+        game.add_player(Box::new(Slow { time : Rc::clone(&time), thinking : Duration::from_secs(1) }));
+        game.add_player(Box::new(Slow { time, thinking : Duration::from_secs(1) }));
+        game.set_first(0);
+        game.play_turn().unwrap();
+
+        let view = game.view(0);
+        let aa = |x, word : &str| Move::new(x, 7, alphabet.encode(word).unwrap(), Direction::Horizontal);
+        assert_eq!(view.preview(&aa(7, "AA")).unwrap().total(), 4);
+        assert_eq!(view.preview(&aa(7, "AAA")), Err(MoveError::UnknownWord("AAA".to_string())));
+        assert_eq!(view.preview(&aa(14, "AA")), Err(MoveError::CantPlace("AA".to_string())));
+        // The other hand got the last tile
+        assert!(matches!(game.view(1).preview(&aa(7, "AA")), Err(MoveError::MissingLetters(_))));
+    }
+
+    #[test]
+    fn move_rules() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let tile = |label : &str| Tile::new(ab.letter(label).unwrap(), 1, false);
+        let at = |x, y, word : &str, direction| Move::new(x, y, ab.encode(word).unwrap(), direction);
+        let dico = Arc::new(Dico::from_words(vec!["AA", "AT", "TA"], ab));
+        let time = Rc::new(Cell::new(Duration::from_secs(0)));
+        let mut game = Game::new(&tileset, Arc::clone(&dico));
+        game.add_player(Box::new(Slow { time, thinking : Duration::from_secs(1) }));
+        let mut position = Position::new(Board::new());
+        position.set_rack(0, vec![tile("A"), tile("T")]);
+        game.set_position(&position, &tileset);
+
+        // The first word covers the center
+        let view = game.view(0);
+        assert_eq!(view.preview(&at(0, 0, "AT", Direction::Horizontal)), Err(MoveError::OffCenter("AT".to_string())));
+        assert!(view.preview(&at(6, 7, "AT", Direction::Horizontal)).is_ok());
+
+        let mut board = Board::new();
+        board.add_move(at(7, 7, "AT", Direction::Horizontal), vec![tile("A"), tile("T")]);
+        let mut position = Position::new(board);
+        position.set_rack(0, vec![tile("A"), tile("T")]);
+        game.set_position(&position, &tileset);
+        let view = game.view(0);
+        // The next ones touch the board
+        assert_eq!(view.preview(&at(0, 0, "AT", Direction::Horizontal)), Err(MoveError::NotConnected("AT".to_string())));
+        assert!(view.preview(&at(8, 7, "TA", Direction::Vertical)).is_ok());
+        // TA right before AT really makes TAAT
+        assert_eq!(view.preview(&at(5, 7, "TA", Direction::Horizontal)), Err(MoveError::Extended("TA".to_string())));
+        assert_eq!(view.preview(&at(7, 5, "TA", Direction::Vertical)), Err(MoveError::Extended("TA".to_string())));
+        // AT under AT makes AA and TT across
+        assert_eq!(view.preview(&at(7, 8, "AT", Direction::Horizontal)), Err(MoveError::UnknownWord("TT".to_string())));
+    }
+
+    /// A player placing a move, then passing
+    struct Placer(Option<Move>);
+
+    impl PlayerTrait for Placer {
+        fn name(&self) -> &str {
+            "placer"
+        }
+
+        fn play(&mut self, _game : &GameView, _hand : &Hand) -> Action {
+            self.0.take().map_or(Action::Pass, Action::Place)
+        }
+
+        fn move_score(&mut self, _score : u32) {
+        }
+
+        fn total_score(&mut self, _score : u32) {
+        }
+    }
+
+    #[test]
+    fn turn_flow() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let tile = |label : &str| {
+            let letter = ab.letter(label).unwrap();
+            Tile::new(letter, tileset.get_points(letter), false)
+        };
+        let draws = [(0, tile("Z")), (1, Tile::new(0, 0, true)), (2, tile("A"))];
+        assert_eq!(closest_to_a(&draws), vec![1]);
+        assert_eq!(closest_to_a(&[(0, tile("A")), (1, tile("B")), (2, tile("A"))]), vec![0, 2]);
+
+        // Everybody draws once the first player is known, the tiles drawn for
+        // it going back in the bag
+        let dico = Arc::new(Dico::from_words(vec!["AB"], ab));
+        let mut game = Game::new(&tileset, Arc::clone(&dico));
+        for _ in 0..3 {
+            game.add_player(Box::new(Placer(None)));
+        }
+        game.start();
+        let first = game.current();
+        assert_eq!(game.view(0).bag_len(), 100 - 21);
+        // Nobody places a word for three rounds
+        for turn in 0..9 {
+            assert!(!game.is_over());
+            assert!(matches!(game.play_turn(), Ok(Turn::Passed)));
+            assert_eq!(game.current(), (first + turn + 1) % 3);
+        }
+        assert!(game.is_over() && game.out().is_none());
+        assert_eq!(game.round(), 4);
+        // Each loses the points of its own tiles
+        for standing in game.end() {
+            let left : i32 = standing.rack.iter().map(|t| t.points() as i32).sum();
+            assert_eq!((standing.rack.len(), standing.tiles), (7, -left));
+        }
+
+        // The first one goes out and gets the points of the others
+        let mut game = Game::new(&tileset, dico);
+        let ab_move = Move::new(7, 7, ab.encode("AB").unwrap(), Direction::Horizontal);
+        game.add_player(Box::new(Placer(Some(ab_move))));
+        game.add_player(Box::new(Placer(None)));
+        game.add_player(Box::new(Placer(None)));
+        let mut position = Position::new(Board::new());
+        position.set_rack(0, vec![tile("A"), tile("B")]);
+        position.set_rack(1, vec![tile("B")]);
+        position.set_rack(2, vec![tile("A"), tile("A")]);
+        position.set_bag(Some(Vec::new()));
+        game.set_position(&position, &tileset);
+        assert!(matches!(game.play_turn(), Ok(Turn::Placed(_))));
+        assert!(game.is_over());
+        assert_eq!(game.out(), Some(0));
+        let standings = game.end();
+        let summary : Vec<(usize, u32, i32)> = standings.iter().map(|s| (s.player, s.score, s.tiles)).collect();
+        assert_eq!(summary, vec![(0, 8 + 5, 5), (1, 0, -3), (2, 0, -2)]);
+    }
+}
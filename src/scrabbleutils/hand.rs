@@ -1,25 +1,47 @@
-const HAND_CAPACITY : usize = 7;
-use super::{Tile, TileBag};
+/// How many tiles a hand holds unless the tileset says otherwise
+pub(super) const HAND_CAPACITY : usize = 7;
+use super::{Tile, TileBag, Letter};
 
 /// Stores a vector of tiles
 pub struct Hand {
     tiles : Vec<Tile>,
+    /// How many tiles the hand is filled up to
+    capacity : usize,
 }
 
 impl Hand {
     /// Create a new empty Hand
     pub fn new() -> Hand {
+        Hand::with_capacity(HAND_CAPACITY)
+    }
+
+    /// Create a new empty Hand holding up to `capacity` tiles
+    pub fn with_capacity(capacity : usize) -> Hand {
         Hand {
-            tiles : Vec::with_capacity(HAND_CAPACITY),
+            tiles : Vec::with_capacity(capacity),
+            capacity,
         }
     }
 
+    /// Create a Hand holding `tiles`
+    pub fn from_tiles(tiles : Vec<Tile>) -> Hand {
+        Hand {
+            capacity : tiles.len().max(HAND_CAPACITY),
+            tiles,
+        }
+    }
+
+    /// Hold `tiles` instead of the tiles of the hand, up to the same capacity
+    pub(super) fn set_tiles(&mut self, tiles : Vec<Tile>) {
+        self.tiles = tiles;
+    }
+
     /// Take tiles from a bag
     ///
     /// Argument:
     /// * `bag` - A mutable reference to the Bag to draw from
     pub fn draw(&mut self, bag : &mut TileBag) {
-        while self.tiles.len() < HAND_CAPACITY {
+        while self.tiles.len() < self.capacity {
             let new_tile = bag.pick();
             if let None = new_tile {
                 return;
@@ -37,14 +59,14 @@ impl Hand {
     ///
     /// # Argument
     /// `remove` - The chars to remove
-    pub fn remove(&mut self, remove : &Vec<char>) -> Option<Vec<Tile>> {
+    pub fn remove(&mut self, remove : &[Letter]) -> Option<Vec<Tile>> {
         let mut ret : Vec<Tile> = Vec::with_capacity(7);
         if !self.contains(remove) {
             return None;
         }
         for c in remove {
             match self.tiles.iter()
-                    .position(|tile| tile.letter() == *c) {
+                    .position(|tile| !tile.wildcard() && tile.letter() == *c) {
                 None => {
                     // We don't have this letter, but we have a wildcard
                     let index = self.tiles.iter()
@@ -64,6 +86,24 @@ impl Hand {
         }
     }
 
+    /// Remove the given tiles and return them
+    ///
+    /// A wildcard is only removed by giving a wildcard.
+    ///
+    /// # Return Value
+    /// None, leaving the hand untouched, if one of the tiles is not in it.
+    pub fn remove_tiles(&mut self, remove : &[Tile]) -> Option<Vec<Tile>> {
+        let mut left = self.tiles.clone();
+        let mut ret : Vec<Tile> = Vec::with_capacity(remove.len());
+        for tile in remove {
+            let index = left.iter().position(|t| t.wildcard() == tile.wildcard()
+                && (t.wildcard() || t.letter() == tile.letter()))?;
+            ret.push(left.swap_remove(index));
+        }
+        self.tiles = left;
+        Some(ret)
+    }
+
     /// Get a copy of the tiles in hand
     pub fn get(&self) -> Vec<Tile> {
         self.tiles.clone()
@@ -74,10 +114,10 @@ impl Hand {
     /// Tells if there is a different tile for each character in `elem`
     /// # Argument
     /// * `elem` - The vector of letter to verify they match a different tile
-    pub fn contains(&self, elem : &Vec<char>) -> bool {
+    pub fn contains(&self, elem : &[Letter]) -> bool {
         let mut tmp_tiles = self.tiles.clone();
         for c in elem {
-            match tmp_tiles.iter().position(|tile| tile.letter() == *c) {
+            match tmp_tiles.iter().position(|tile| !tile.wildcard() && tile.letter() == *c) {
                 None => {
                     // We don't have this letter, but we have a wildcard
                     match tmp_tiles.iter().position(|tile| tile.wildcard() == true) {
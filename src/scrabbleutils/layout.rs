@@ -0,0 +1,161 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader};
+use super::Board;
+use super::bonuses::{WordBonus, LetterBonus};
+
+/// Starts a comment in a layout file
+const COMMENT : char = '#';
+/// What each bonus is written with in a layout file
+const SPOTS : [(char, LetterBonus, WordBonus); 5] = [
+    ('.', LetterBonus::None, WordBonus::None),
+    ('d', LetterBonus::Double, WordBonus::None),
+    ('t', LetterBonus::Triple, WordBonus::None),
+    ('D', LetterBonus::None, WordBonus::Double),
+    ('T', LetterBonus::None, WordBonus::Triple),
+];
+
+/// Why a board layout could not be read
+#[derive(Debug)]
+pub enum LayoutError {
+    /// The file could not be read
+    Io(io::Error),
+    /// A line is malformed, positions start at 1
+    Syntax {
+        line : usize,
+        column : usize,
+        message : String,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::Io(e) => write!(f, "{}", e),
+            LayoutError::Syntax { line, column, message } =>
+                write!(f, "line {}, column {}: {}", line, column, message),
+        }
+    }
+}
+
+impl From<io::Error> for LayoutError {
+    fn from(e : io::Error) -> LayoutError {
+        LayoutError::Io(e)
+    }
+}
+
+impl Board {
+    /// Create an empty board from a layout file
+    ///
+    /// See `from_layout()` for the format of the file.
+    ///
+    /// # Errors
+    /// If the file can't be read or is malformed.
+    pub fn from_layout_file(filename : &str) -> Result<Board, LayoutError> {
+        let file = File::open(filename)?;
+        Board::from_layout(BufReader::new(file))
+    }
+
+    /// Create an empty board from its layout
+    ///
+    /// The layout is 15 lines of 15 spots, from the top left corner. Each
+    /// spot is written with one character, spaces between them are allowed:
+    /// * `.` - No bonus.
+    /// * `d`, `t` - Double or triple letter.
+    /// * `D`, `T` - Double or triple word.
+    ///
+    /// Blank lines are ignored, and a `#` starts a comment up to the end of
+    /// the line.
+    ///
+    /// # Errors
+    /// If a spot is unknown or the layout is not 15 by 15, with its position.
+    pub fn from_layout<R : BufRead>(reader : R) -> Result<Board, LayoutError> {
+        let mut board = Board::new();
+        let mut y = 0;
+        let mut last_line = 0;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            last_line = index + 1;
+            let error = |column : usize, message : String| LayoutError::Syntax {
+                line : index + 1,
+                column,
+                message,
+            };
+            let content = line.split(COMMENT).next().unwrap_or("");
+            let spots : Vec<(usize, char)> = content.chars().enumerate()
+                .filter(|(_, c)| !c.is_whitespace())
+                .map(|(column, c)| (column + 1, c))
+                .collect();
+            if spots.is_empty() {
+                continue;
+            }
+            if y == 15 {
+                return Err(error(spots[0].0, "the board has only 15 rows".to_string()));
+            }
+            if spots.len() != 15 {
+                return Err(error(spots[0].0, format!("a row has 15 spots, not {}", spots.len())));
+            }
+            for (x, (column, c)) in spots.into_iter().enumerate() {
+                let (_, letter, word) = SPOTS.iter().find(|s| s.0 == c).ok_or_else(||
+                    error(column, format!("`{}` is not a spot, use one of . d t D T", c)))?;
+                board.set_bonuses(x as u8, y, *letter, *word);
+            }
+            y += 1;
+        }
+        if y != 15 {
+            return Err(LayoutError::Syntax {
+                line : last_line,
+                column : 1,
+                message : format!("the board has 15 rows, not {}", y),
+            });
+        }
+        Ok(board)
+    }
+
+    /// Write the layout of the board, the way `from_layout()` reads it
+    pub fn layout(&self) -> String {
+        let mut layout = String::with_capacity(16 * 15);
+        for y in 0..15 {
+            for x in 0..15 {
+                let (letter, word) = self.get_bonuses(x, y);
+                let spot = match (letter, word) {
+                    (_, WordBonus::Triple) => 'T',
+                    (_, WordBonus::Double) => 'D',
+                    (LetterBonus::Triple, _) => 't',
+                    (LetterBonus::Double, _) => 'd',
+                    _ => '.',
+                };
+                layout.push(spot);
+            }
+            layout.push('\n');
+        }
+        layout
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Board;
+    use super::LayoutError;
+
+    #[test]
+    fn layouts() {
+        let standard = Board::from_layout_file("standard_layout.txt").unwrap();
+        assert_eq!(standard.layout(), Board::new().layout());
+        assert!(standard.layout().starts_with("T..d...T...d..T\n.D...t...t...D.\n"));
+
+        let mut layout = "...............\n".repeat(15);
+        layout.replace_range(7..8, "T");
+        let board = Board::from_layout(layout.as_bytes()).unwrap();
+        assert_eq!(board.layout(), layout);
+
+        let error = |layout : &str| match Board::from_layout(layout.as_bytes()) {
+            Err(LayoutError::Syntax { line, column, .. }) => (line, column),
+            _ => panic!("the layout should be refused"),
+        };
+        assert_eq!(error(&layout.replacen("T", "x", 1)), (1, 8));
+        assert_eq!(error(&layout.replacen(".\n", "\n", 1)), (1, 1));
+        assert_eq!(error(&"...............\n".repeat(14)), (14, 1));
+        assert_eq!(error(&"...............\n".repeat(16)), (16, 1));
+    }
+}
@@ -12,11 +12,26 @@ pub mod bonuses;
 mod spot;
 /// Where the dictionnary is stored
 mod dico;
-mod gaddag;
+/// Searches in the dictionnary: anagrams, patterns and hooks
+mod query;
 /// The bag we draw tiles from
 mod tilebag;
 /// The way we describe moves
 mod smove;
+/// Moves written the standard way, like `8H HELLO`
+mod notation;
+/// Boards with other bonuses, read from files
+mod layout;
+/// Games saved and read in the GCG format
+mod record;
+/// Find every move a hand can make on a board
+mod movegen;
+/// The turns of a game compared with the best moves
+mod analysis;
+/// Positions set up by hand, to study them
+mod position;
+/// The moves of a pre-endgame judged against every rack of the opponent
+mod preendgame;
 /// The player's Hand, stores tiles and provide a few function.
 mod hand;
 /// All about the configurable stuff
@@ -25,16 +40,30 @@ mod hand;
 mod config;
 /// The small tiles we place in the game
 mod tile;
+/// The letters of a game, as written on the tiles
+mod alphabet;
+/// The time of the players
+mod clock;
+/// Runs a game: turns, moves checking, scores and clocks
+mod game;
+/// What the game tells its observers
+mod event;
 
 /// Interface to make a player
 pub use player::PlayerTrait;
-pub use board::Board;
-pub use dico::Dico;
+pub use board::{Board, ScoreBreakdown};
+pub use dico::{Dico, DicoBuilder};
+pub use query::PatternError;
 pub use tilebag::TileBag;
 /// The way we tell what we want to play
 pub use smove::Move;
 pub use tile::Tile;
+pub use alphabet::{Alphabet, Letter};
 pub use smove::Direction;
+/// What a player decided to do on its turn
+pub use smove::Action;
+pub use movegen::{generate_moves, best_moves, Candidate};
+pub use preendgame::{PreEndgame, MoveOutcome};
 /// Part of Move
 pub use hand::Hand;
 /// All the player implementation shell
@@ -42,4 +71,12 @@ pub use player::Player;
 /// Part of the configuration
 ///
 /// Define all the tiles we want in our bag
-pub use config::TileSet;
+pub use config::{TileSet, TileSetError, WILDCARD};
+pub use clock::{Clock, ClockSource, SystemClock, TimeControl, OVERTIME_PENALTY};
+pub use game::{Game, GameView, MoveError, Turn};
+pub use notation::parse_square;
+pub use layout::LayoutError;
+pub use record::{GameRecord, GameRecorder, RecordedTurn, RecordError, Play};
+pub use analysis::{GameAnalysis, TurnAnalysis, PlayerSummary};
+pub use position::{Position, PositionError};
+pub use event::{Event, Observer, Standing};
@@ -0,0 +1,290 @@
+use super::{Board, Dico, Hand, Move, Direction, Tile, Letter};
+use std::cmp::Reverse;
+
+/// A move found by `generate_moves()`
+pub struct Candidate {
+    /// The move itself
+    mv : Move,
+    /// The tiles from the hand it needs, in the order they are placed
+    tiles : Vec<Tile>,
+    /// What the move would score
+    score : u32,
+}
+
+impl Candidate {
+    /// Get the move
+    pub fn mv(&self) -> &Move {
+        &self.mv
+    }
+
+    /// Get the tiles taken from the hand, wildcards already set
+    pub fn tiles(&self) -> &Vec<Tile> {
+        &self.tiles
+    }
+
+    /// Get the score of the move
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+}
+
+/// Everything that does not change while searching a line
+struct Line<'a> {
+    board : &'a Board,
+    dico : &'a Dico,
+    direction : Direction,
+    /// Index of the row (horizontal) or column (vertical)
+    index : u8,
+    /// Whether the board has no tile yet
+    first_move : bool,
+    /// Index in the line where the word starts
+    start : u8,
+}
+
+impl<'a> Line<'a> {
+    /// Get the board position of the `pos`th spot of the line
+    fn coords(&self, pos : u8) -> (u8, u8) {
+        match self.direction {
+            Direction::Horizontal => (pos, self.index),
+            Direction::Vertical => (self.index, pos),
+        }
+    }
+
+    /// Get the letter on the `pos`th spot of the line
+    fn letter(&self, pos : u8) -> Option<Letter> {
+        let (x, y) = self.coords(pos);
+        self.board.get_letter(x, y)
+    }
+
+    /// Check the word made perpendicularly by placing `c` at `pos`
+    ///
+    /// # Return Value
+    /// None if it makes an unknown word, otherwise whether it made a word
+    fn cross_check(&self, pos : u8, c : Letter) -> Option<bool> {
+        let (x, y) = self.coords(pos);
+        let (offset_x, offset_y) : (i16, i16) = match self.direction {
+            Direction::Horizontal => (0, 1),
+            Direction::Vertical => (1, 0),
+        };
+        // Go back to the first letter of the perpendicular word
+        let mut pos_x = x as i16;
+        let mut pos_y = y as i16;
+        while pos_x - offset_x >= 0 && pos_y - offset_y >= 0 &&
+                self.board.get_letter((pos_x - offset_x) as u8,
+                    (pos_y - offset_y) as u8).is_some() {
+            pos_x -= offset_x;
+            pos_y -= offset_y;
+        }
+
+        let mut word : Vec<Letter> = Vec::new();
+        loop {
+            if pos_x == x as i16 && pos_y == y as i16 {
+                word.push(c);
+            }
+            else {
+                match self.board.get_letter(pos_x as u8, pos_y as u8) {
+                    Some(letter) => word.push(letter),
+                    None => break,
+                }
+            }
+            pos_x += offset_x;
+            pos_y += offset_y;
+        }
+
+        if word.len() == 1 {
+            Some(false)
+        }
+        else if self.dico.exists(&word) {
+            Some(true)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Extend `word` from the `pos`th spot with what the board and `rack` offer
+    ///
+    /// # Arguments
+    /// * `pos` - The next spot to fill
+    /// * `word` - The word made so far from `start`
+    /// * `rack` - The tiles still available
+    /// * `used` - The tiles placed so far
+    /// * `connected` - Whether the word touches the tiles already on the board
+    /// * `out` - Where the found moves go
+    fn extend(&self, pos : u8, word : &mut Vec<Letter>, rack : &mut Vec<Tile>,
+            used : &mut Vec<Tile>, connected : bool, out : &mut Vec<Candidate>) {
+        let ends_here = pos == 15 || self.letter(pos).is_none();
+        if ends_here && connected && !used.is_empty() &&
+                word.len() > 1 && self.dico.exists(word) {
+            let (x, y) = self.coords(self.start);
+            let mv = Move::new(x, y, word.clone(), self.direction);
+            let score = self.board.score(&mv, used);
+            out.push(Candidate { mv, tiles : used.clone(), score });
+        }
+        if pos == 15 {
+            return;
+        }
+
+        if let Some(c) = self.letter(pos) {
+            word.push(c);
+            if self.dico.is_prefix(word) {
+                self.extend(pos + 1, word, rack, used, true, out);
+            }
+            word.pop();
+            return;
+        }
+
+        let (x, y) = self.coords(pos);
+        let on_center = self.first_move && x == 7 && y == 7;
+        for i in 0..rack.len() {
+            // The same tile twice would give the same moves
+            if rack[..i].contains(&rack[i]) {
+                continue;
+            }
+            let letters : Vec<Letter> = if rack[i].wildcard() {
+                self.dico.alphabet().letters().collect()
+            }
+            else {
+                vec![rack[i].letter()]
+            };
+            for c in letters {
+                word.push(c);
+                if self.dico.is_prefix(word) {
+                    if let Some(crossed) = self.cross_check(pos, c) {
+                        let tile = rack.remove(i);
+                        let mut placed = tile.clone();
+                        placed.set_wildcard(c);
+                        used.push(placed);
+                        self.extend(pos + 1, word, rack, used,
+                            connected || crossed || on_center, out);
+                        used.pop();
+                        rack.insert(i, tile);
+                    }
+                }
+                word.pop();
+            }
+        }
+    }
+}
+
+/// Find every move `hand` can make on `board`
+///
+/// A move is kept when all the words it makes exist in `dico`, it touches
+/// the tiles already on the board (or covers the center for the first move)
+/// and it places at least one tile.
+///
+/// # Return Value
+/// The moves, in no particular order.
+pub fn generate_moves(board : &Board, hand : &Hand, dico : &Dico) -> Vec<Candidate> {
+    let mut out : Vec<Candidate> = Vec::new();
+    let mut first_move = true;
+    for y in 0..15 {
+        for x in 0..15 {
+            if board.get_letter(x, y).is_some() {
+                first_move = false;
+            }
+        }
+    }
+
+    let mut rack = hand.get();
+    for direction in [Direction::Horizontal, Direction::Vertical].iter() {
+        for index in 0..15 {
+            for start in 0..15 {
+                let line = Line {
+                    board, dico, direction : *direction, index, first_move, start,
+                };
+                // A word can't start right after another letter
+                if start > 0 && line.letter(start - 1).is_some() {
+                    continue;
+                }
+                line.extend(start, &mut Vec::new(), &mut rack,
+                    &mut Vec::new(), false, &mut out);
+            }
+        }
+    }
+    out
+}
+
+/// Find the best scoring moves a hand can make on a board
+///
+/// # Return Value
+/// At most `count` moves, the best first.
+pub fn best_moves(board : &Board, hand : &Hand, dico : &Dico, count : usize) -> Vec<Candidate> {
+    let mut moves = generate_moves(board, hand, dico);
+    moves.sort_by_key(|c| Reverse(c.score()));
+    moves.truncate(count);
+    moves
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Board, Dico, Hand, Move, Direction, Tile, TileSet};
+
+    /// Get a hand with exactly `letters`, `*` being a wildcard
+    fn hand(tileset : &TileSet, letters : &str) -> Hand {
+        Hand::from_tiles(letters.chars().map(|c| match c {
+            '*' => Tile::new(0, 0, true),
+            _ => Tile::new(tileset.alphabet().letter(&c.to_string()).unwrap(), 1, false),
+        }).collect())
+    }
+
+    #[test]
+    fn first_move_covers_center() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let dico = Dico::from_file("dico.txt", tileset.alphabet()).unwrap();
+        let board = Board::new();
+
+        let candidates = super::generate_moves(&board, &hand(&tileset, "HELLOIT"), &dico);
+        let hello = tileset.alphabet().encode("HELLO").unwrap();
+        assert!(candidates.iter().any(|c| c.mv().word() == &hello[..]));
+        for candidate in candidates {
+            let mv = candidate.mv();
+            let len = mv.word().len() as u8;
+            match mv.direction() {
+                Direction::Horizontal => {
+                    assert_eq!(mv.y(), 7);
+                    assert!(mv.x() <= 7 && mv.x() + len > 7);
+                }
+                Direction::Vertical => {
+                    assert_eq!(mv.x(), 7);
+                    assert!(mv.y() <= 7 && mv.y() + len > 7);
+                }
+            }
+            assert!(dico.exists(mv.word()));
+        }
+
+        let best = super::best_moves(&board, &hand(&tileset, "HELLOIT"), &dico, 2);
+        assert_eq!(best.len(), 2);
+        assert!(best[0].score() >= best[1].score());
+        let candidates = super::generate_moves(&board, &hand(&tileset, "HELLOIT"), &dico);
+        assert!(candidates.iter().all(|c| c.score() <= best[0].score()));
+    }
+
+    #[test]
+    fn plays_through_board_letters() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let dico = Dico::from_file("dico.txt", ab).unwrap();
+        let mut board = Board::new();
+        let hello = ab.encode("HELLO").unwrap();
+        let tiles = hello.iter().map(|c| Tile::new(*c, 1, false)).collect();
+        board.add_move(Move::new(5, 7, hello, Direction::Horizontal), tiles);
+
+        let candidates = super::generate_moves(&board, &hand(&tileset, "MTS"), &dico);
+        let found = |word : &str, x : u8, y : u8| candidates.iter().any(|c|
+            ab.decode(c.mv().word()) == word && c.mv().x() == x && c.mv().y() == y);
+        // ME going down through the E of HELLO
+        assert!(found("ME", 6, 6));
+        // MARIO would need an A on the board
+        assert!(!found("MARIO", 6, 6));
+
+        // The wildcard stands for the M
+        let candidates = super::generate_moves(&board, &hand(&tileset, "*TS"), &dico);
+        let me = candidates.iter().find(|c| ab.decode(c.mv().word()) == "ME").unwrap();
+        assert!(me.tiles()[0].wildcard());
+        assert_eq!(ab.label(me.tiles()[0].letter()), "M");
+        for candidate in candidates.iter() {
+            assert!(dico.exists(candidate.mv().word()));
+        }
+    }
+}
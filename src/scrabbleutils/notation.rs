@@ -0,0 +1,103 @@
+use super::{Move, Direction, Alphabet};
+
+/// The letters naming the columns, from left to right
+const COLUMNS : &str = "ABCDEFGHIJKLMNO";
+
+/// Read coordinates like `8H` or `H8`
+///
+/// # Return Value
+/// The column, the row and the direction, starting at 0.
+pub(super) fn parse_coordinates(coordinates : &str) -> Option<(u8, u8, Direction)> {
+    let coordinates = coordinates.to_uppercase();
+    let digits = coordinates.find(|c : char| !c.is_ascii_digit()).unwrap_or(coordinates.len());
+    let (row, column, direction) = if digits > 0 {
+        // The row first is for a horizontal word
+        let (row, column) = coordinates.split_at(digits);
+        (row, column, Direction::Horizontal)
+    }
+    else {
+        // The column can be any letter, a wrong one is refused below
+        let first = coordinates.chars().next()?;
+        let (column, row) = coordinates.split_at(first.len_utf8());
+        (row, column, Direction::Vertical)
+    };
+    let row : u8 = row.parse().ok()?;
+    if !(1..=15).contains(&row) || column.chars().count() != 1 {
+        return None;
+    }
+    let column = COLUMNS.find(column)? as u8;
+    Some((column, row - 1, direction))
+}
+
+/// Read the coordinates of a spot, like `H8` or `8H`
+///
+/// # Return Value
+/// The column and the row, starting at 0.
+pub fn parse_square(coordinates : &str) -> Option<(u8, u8)> {
+    parse_coordinates(coordinates).map(|(x, y, _)| (x, y))
+}
+
+impl Move {
+    /// Read a move written in the standard notation
+    ///
+    /// The coordinates come before the word, like `8H HELLO`. When the row
+    /// comes first the word is horizontal, when the column comes first it is
+    /// vertical, like `H8 HELLO`. Columns go from `A` to `O` and rows from 1
+    /// to 15.
+    ///
+    /// # Return Value
+    /// None if the coordinates are malformed or the word has letters that are
+    /// not in the `alphabet`.
+    pub fn from_notation(notation : &str, alphabet : &Alphabet) -> Option<Move> {
+        let parts : Vec<&str> = notation.split_whitespace().collect();
+        if parts.len() != 2 {
+            return None;
+        }
+        let (x, y, direction) = parse_coordinates(parts[0])?;
+        let word = alphabet.encode(parts[1])?;
+        Some(Move::new(x, y, word, direction))
+    }
+
+    /// Write the move in the standard notation, like `8H HELLO`
+    pub fn notation(&self, alphabet : &Alphabet) -> String {
+        let column = &COLUMNS[self.x() as usize..self.x() as usize + 1];
+        let coordinates = match self.direction() {
+            Direction::Horizontal => format!("{}{}", self.y() + 1, column),
+            Direction::Vertical => format!("{}{}", column, self.y() + 1),
+        };
+        format!("{} {}", coordinates, alphabet.decode(self.word()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Move, Alphabet, Direction};
+    use super::{parse_square, parse_coordinates};
+
+    #[test]
+    fn notation() {
+        let alphabet = Alphabet::new(["E", "H", "L", "O"].iter().map(|l| l.to_string()).collect());
+        let hello = Move::from_notation("8h hello", &alphabet).unwrap();
+        assert_eq!((hello.x(), hello.y()), (7, 7));
+        assert!(matches!(hello.direction(), Direction::Horizontal));
+        assert_eq!(hello.notation(&alphabet), "8H HELLO");
+
+        let down = Move::from_notation("O15 HE", &alphabet).unwrap();
+        assert_eq!((down.x(), down.y()), (14, 14));
+        assert!(matches!(down.direction(), Direction::Vertical));
+        assert_eq!(down.notation(&alphabet), "O15 HE");
+
+        assert!(Move::from_notation("16A HE", &alphabet).is_none());
+        assert!(Move::from_notation("8P HE", &alphabet).is_none());
+        assert!(Move::from_notation("8H", &alphabet).is_none());
+        assert!(Move::from_notation("8H HAT", &alphabet).is_none());
+        assert!(Move::from_notation("É8 HELLO", &alphabet).is_none());
+        assert!(Move::from_notation("8É HELLO", &alphabet).is_none());
+
+        assert_eq!(parse_square("h10"), Some((7, 9)));
+        assert_eq!(parse_square("10H"), Some((7, 9)));
+        assert_eq!(parse_square("H0"), None);
+        assert!(parse_coordinates("É8").is_none());
+        assert!(parse_coordinates("").is_none());
+    }
+}
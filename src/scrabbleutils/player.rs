@@ -1,37 +1,59 @@
-use super::{Board, Move, Hand};
+use super::{GameView, Action, Hand, Event, Standing};
 
 /// Gives a simple player interface to work with
+///
+/// The lifecycle hooks do nothing unless implemented.
 pub trait PlayerTrait {
     /// Must return the player's name
     fn name(&self) -> &str;
     /// Called when it's the player turn.
     ///
     /// # Arguments
-    /// * `board` - The game board in its current state.
+    /// * `game` - What you know about the game: the board, the scores, the
+    ///   bag, the moves played so far and the clocks.
     /// * `hand` - The struct that stores your tiles.
     ///
     /// # Return Value
-    /// A move structure.
-    /// it before adding it to the game.
-    fn play(&self, board : &Board, hand : &Hand) -> Move;
+    /// What the player wants to do, the game will check a placed move
+    /// before adding it to the board.
+    fn play(&mut self, game : &GameView, hand : &Hand) -> Action;
     /// Gives you the score for your move.
     ///
     /// # Arguments
     /// * `score` - The score your move made.
-    fn move_score(&self, score : u32);
+    fn move_score(&mut self, score : u32);
     /// Gives you the sum of all your moves' score.
     ///
     /// # Arguments
     /// * `score` - Your score.
-    fn total_score(&self, score : u32);
+    fn total_score(&mut self, score : u32);
+    /// Called when the game starts, before the first turn.
+    ///
+    /// # Arguments
+    /// * `game` - What you know about the game.
+    fn new_game(&mut self, _game : &GameView) {
+    }
+    /// Called after each turn of an opponent.
+    ///
+    /// # Arguments
+    /// * `game` - What you know about the game, the turn included.
+    /// * `event` - What the opponent did: a move placed, an exchange or a pass.
+    fn opponent_moved(&mut self, _game : &GameView, _event : &Event) {
+    }
+    /// Called when the game is over.
+    ///
+    /// # Arguments
+    /// * `standings` - Where each player stands, the best first.
+    fn game_over(&mut self, _standings : &[Standing]) {
+    }
 }
 
 /// The Player Struct
 ///
-/// Used by the main function or something.
+/// What the game knows about a player, its interface aside.
 pub struct Player {
-    /// A dynamic pointer to the player implementation
-    pub player: Box<dyn PlayerTrait>,
+    /// The player's name
+    pub name: String,
     /// The player's hand
     pub hand: Hand,
     /// The player's score
@@ -42,13 +64,13 @@ impl Player {
     /// Create a new Player structure
     ///
     /// # Argument
-    /// * `player` - A pointer to an implementation of the PlayerTrait.
+    /// * `name` - The name of the player.
     ///
     /// # Return Value
     /// A Player structure with an empty hand and a score of 0.
-    pub fn new(player: Box<dyn PlayerTrait>) -> Player {
+    pub fn new(name: String) -> Player {
         Player {
-            player,
+            name,
             hand : Hand::new(),
             score : 0,
         }
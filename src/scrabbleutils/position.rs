@@ -0,0 +1,200 @@
+use std::fmt;
+use super::{Board, Tile, TileSet, Letter};
+
+/// Stands for a wildcard when a position tells what is wrong
+const BLANK_LABEL : &str = "?";
+
+/// A game set up by hand, to study a position from a real game
+///
+/// The tiles are put on the board one by one, without making moves. The
+/// racks and the bag can be given too, the bag being the tiles not seen
+/// otherwise.
+pub struct Position {
+    board : Board,
+    /// The rack of each player, in the order they play
+    racks : Vec<Vec<Tile>>,
+    /// None for the tiles of the tileset not on the board or in a rack
+    bag : Option<Vec<Tile>>,
+}
+
+/// Why a position can't come from a game
+#[derive(Debug, PartialEq)]
+pub enum PositionError {
+    /// There are more tiles with a letter than the tileset has, `?` standing
+    /// for the wildcards
+    TooMany {
+        label : String,
+        count : u32,
+        available : u32,
+    },
+    /// A rack holds more tiles than a hand can
+    RackTooBig {
+        player : usize,
+        size : usize,
+        capacity : usize,
+    },
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositionError::TooMany { label, count, available } =>
+                write!(f, "there are {} tiles {} but the game has {}", count, label, available),
+            PositionError::RackTooBig { player, size, capacity } =>
+                write!(f, "the rack of player {} has {} tiles, a hand holds {}", player + 1, size, capacity),
+        }
+    }
+}
+
+/// What is counted for each tile: its letter, or None for a wildcard
+fn kind(tile : &Tile) -> Option<Letter> {
+    if tile.wildcard() { None } else { Some(tile.letter()) }
+}
+
+impl Position {
+    /// Create a position on `board`, which may already hold tiles
+    pub fn new(board : Board) -> Position {
+        Position {
+            board,
+            racks : Vec::new(),
+            bag : None,
+        }
+    }
+
+    /// Get the board
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Put `tile` at a spot of the board, or take the tile there if None
+    ///
+    /// A blank is a wildcard tile with the letter it stands for.
+    ///
+    /// # Panic
+    /// If the spot is out of the board.
+    pub fn set_tile(&mut self, x : u8, y : u8, tile : Option<Tile>) {
+        assert!(x < 15 && y < 15);
+        self.board.set_tile(x, y, tile);
+    }
+
+    /// Get the rack of `player`, empty if it was not given
+    pub fn rack(&self, player : usize) -> &[Tile] {
+        self.racks.get(player).map_or(&[], |rack| &rack[..])
+    }
+
+    /// Give the rack of `player`, starting at 0
+    pub fn set_rack(&mut self, player : usize, rack : Vec<Tile>) {
+        if self.racks.len() <= player {
+            self.racks.resize(player + 1, Vec::new());
+        }
+        self.racks[player] = rack;
+    }
+
+    /// Give what is in the bag, None for the tiles not seen elsewhere
+    pub fn set_bag(&mut self, bag : Option<Vec<Tile>>) {
+        self.bag = bag;
+    }
+
+    /// Get the tiles of `tileset` that are neither on the board nor in a rack
+    ///
+    /// A blank is told apart from the other tiles, whatever letter it stands
+    /// for. There are none of a letter when the position has too many.
+    pub fn unseen(&self, tileset : &TileSet) -> Vec<Tile> {
+        let mut seen : Vec<Tile> = self.racks.iter().flatten().cloned().collect();
+        seen.extend(self.board_tiles());
+        tileset.unseen(&seen)
+    }
+
+    /// Get the tiles in the bag
+    pub fn bag(&self, tileset : &TileSet) -> Vec<Tile> {
+        match &self.bag {
+            Some(bag) => bag.clone(),
+            None => self.unseen(tileset),
+        }
+    }
+
+    /// Get the tiles on the board
+    fn board_tiles(&self) -> Vec<Tile> {
+        let mut tiles : Vec<Tile> = Vec::new();
+        for y in 0..15 {
+            for x in 0..15 {
+                tiles.extend(self.board.get_tile(x, y));
+            }
+        }
+        tiles
+    }
+
+    /// Check the position could come from a game with `tileset`
+    ///
+    /// # Errors
+    /// If a letter is on more tiles than the tileset has, counting the board,
+    /// the racks and the bag, or if a rack is too big.
+    pub fn check(&self, tileset : &TileSet) -> Result<(), PositionError> {
+        for (player, rack) in self.racks.iter().enumerate() {
+            if rack.len() > tileset.rack_size() {
+                return Err(PositionError::RackTooBig { player, size : rack.len(), capacity : tileset.rack_size() });
+            }
+        }
+        let mut tiles = self.board_tiles();
+        tiles.extend(self.racks.iter().flatten().cloned());
+        tiles.extend(self.bag.iter().flatten().cloned());
+        let mut kinds : Vec<Option<Letter>> = tiles.iter().map(kind).collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        for k in kinds {
+            let count = tiles.iter().filter(|t| kind(t) == k).count() as u32;
+            let available = tileset.infos().iter()
+                .filter(|info| kind(&info.tile()) == k)
+                .map(|info| info.occurences())
+                .sum();
+            if count > available {
+                let label = match k {
+                    Some(letter) => tileset.alphabet().label(letter).to_string(),
+                    None => BLANK_LABEL.to_string(),
+                };
+                return Err(PositionError::TooMany { label, count, available });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Board, Tile, TileSet};
+    use super::{Position, PositionError};
+
+    #[test]
+    fn position() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let tile = |label : &str| {
+            let letter = ab.letter(label).unwrap();
+            Tile::new(letter, tileset.get_points(letter), false)
+        };
+        let total = tileset.infos().iter().map(|i| i.occurences() as usize).sum::<usize>();
+
+        let mut position = Position::new(Board::new());
+        position.set_tile(7, 7, Some(tile("Z")));
+        let mut blank = Tile::new(0, 0, true);
+        blank.set_wildcard(ab.letter("Z").unwrap());
+        position.set_tile(8, 7, Some(blank));
+        position.set_rack(1, vec![tile("A"), Tile::new(0, 0, true)]);
+        assert!(position.rack(0).is_empty());
+        assert_eq!(position.check(&tileset), Ok(()));
+        // No Z and no blank are left
+        let bag = position.bag(&tileset);
+        assert_eq!(bag.len(), total - 4);
+        assert!(bag.iter().all(|t| !t.wildcard() && t.letter() != ab.letter("Z").unwrap()));
+
+        position.set_tile(9, 7, Some(tile("Z")));
+        assert_eq!(position.check(&tileset), Err(PositionError::TooMany {
+            label : "Z".to_string(), count : 2, available : 1 }));
+        position.set_tile(9, 7, None);
+        position.set_bag(Some(vec![Tile::new(0, 0, true)]));
+        assert!(matches!(position.check(&tileset), Err(PositionError::TooMany { count : 3, .. })));
+        position.set_bag(None);
+        position.set_rack(0, vec![tile("E"); 8]);
+        assert!(matches!(position.check(&tileset), Err(PositionError::RackTooBig { player : 0, size : 8, .. })));
+    }
+}
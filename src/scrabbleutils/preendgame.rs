@@ -0,0 +1,250 @@
+use super::{Alphabet, Board, Dico, Hand, Move, Tile, TileSet, Candidate, best_moves};
+use std::collections::HashMap;
+
+/// How a move does against every rack the opponent can hold
+pub struct MoveOutcome {
+    pub mv : Move,
+    pub score : u32,
+    /// The share of the racks the move wins against, a draw counting as half
+    pub win_probability : f64,
+    /// By how many points the player is ahead at the end, on average
+    pub average_spread : f64,
+}
+
+/// The best moves of a pre-endgame, when the bag holds a rack or less
+///
+/// Every way to share the unseen tiles between the rack of the opponent and
+/// the bag is tried, all of them as likely. When the move empties the bag,
+/// the endgame that follows is played out, each side making its best scoring
+/// move. Otherwise only the best scoring reply of the opponent is counted.
+/// The win probability is an estimate: the best scoring moves are not always
+/// the best ones in an endgame.
+pub struct PreEndgame {
+    /// How many tiles are in the bag
+    pub bag : usize,
+    /// How many different racks the opponent can hold
+    pub racks : usize,
+    /// The moves judged, the likeliest to win first
+    pub moves : Vec<MoveOutcome>,
+}
+
+/// A way to share the unseen tiles between the opponent and the bag
+struct Split {
+    rack : Vec<Tile>,
+    bag : Vec<Tile>,
+    /// How many ways to take the tiles give the same rack
+    weight : usize,
+}
+
+/// Get the points of `tiles`
+fn points(tiles : &[Tile]) -> i32 {
+    tiles.iter().map(|tile| tile.points() as i32).sum()
+}
+
+/// Get what is left of `rack` once the `placed` tiles are on the board
+///
+/// A wildcard placed is taken from the wildcards of the rack, whatever
+/// letter it stands for.
+fn left(rack : &[Tile], placed : &[Tile]) -> Vec<Tile> {
+    let mut left = rack.to_vec();
+    for tile in placed {
+        if let Some(index) = left.iter().position(|t| t.wildcard() == tile.wildcard()
+                && (t.wildcard() || t.letter() == tile.letter())) {
+            left.swap_remove(index);
+        }
+    }
+    left
+}
+
+/// Find the best scoring moves `rack` can make on `board`, at most `count`
+fn best(board : &Board, rack : &[Tile], dico : &Dico, count : usize) -> Vec<Candidate> {
+    best_moves(board, &Hand::from_tiles(rack.to_vec()), dico, count)
+}
+
+/// Get every rack of `size` tiles the `tiles` can give, the rest going in
+/// the bag
+fn splits(tiles : &[Tile], size : usize) -> Vec<Split> {
+    let mut splits = HashMap::new();
+    // The indices of the tiles in the rack, increasing
+    let mut chosen : Vec<usize> = (0..size).collect();
+    loop {
+        let mut rack : Vec<Tile> = chosen.iter().map(|i| tiles[*i].clone()).collect();
+        rack.sort_by_key(|tile| (tile.wildcard(), tile.letter()));
+        let key : Vec<_> = rack.iter().map(|tile| (tile.wildcard(), tile.letter())).collect();
+        splits.entry(key).or_insert_with(|| Split {
+            rack,
+            bag : (0..tiles.len()).filter(|i| !chosen.contains(i)).map(|i| tiles[i].clone()).collect(),
+            weight : 0,
+        }).weight += 1;
+        // Move on to the next indices, the last one that can still grow
+        let grown = match (0..size).rev().find(|i| chosen[*i] < tiles.len() - size + i) {
+            Some(grown) => grown,
+            None => break,
+        };
+        chosen[grown] += 1;
+        for i in grown + 1..size {
+            chosen[i] = chosen[i - 1] + 1;
+        }
+    }
+    // The same order every time, for the same sums
+    let mut splits : Vec<_> = splits.into_iter().collect();
+    splits.sort_by(|a, b| a.0.cmp(&b.0));
+    splits.into_iter().map(|(_, split)| split).collect()
+}
+
+/// Play the endgame out, the bag being empty, each side making its best
+/// scoring move until one goes out or both pass
+///
+/// # Arguments
+/// * `board` - The board, the moves are added to it.
+/// * `racks` - The racks of the player and of the opponent.
+/// * `turn` - Who moves first, 0 for the player and 1 for the opponent.
+///
+/// # Return Value
+/// How many points the player makes more than the opponent, the tiles left
+/// in the racks counted.
+fn play_out(board : &mut Board, mut racks : [Vec<Tile>; 2], mut turn : usize, dico : &Dico) -> i32 {
+    // What `turn` makes counts for the player or against it
+    let side = |turn : usize, points : i32| if turn == 0 { points } else { -points };
+    let mut spread = 0;
+    let mut passes = 0;
+    while passes < 2 {
+        match best(board, &racks[turn], dico, 1).pop() {
+            None => passes += 1,
+            Some(candidate) => {
+                passes = 0;
+                spread += side(turn, candidate.score() as i32);
+                racks[turn] = left(&racks[turn], candidate.tiles());
+                board.add_move(candidate.mv().clone(), candidate.tiles().clone());
+                if racks[turn].is_empty() {
+                    // The tiles of the other rack go to the one out
+                    return spread + side(turn, 2 * points(&racks[1 - turn]));
+                }
+            }
+        }
+        turn = 1 - turn;
+    }
+    spread - points(&racks[0]) + points(&racks[1])
+}
+
+impl PreEndgame {
+    /// Judge the best scoring moves of the player to move
+    ///
+    /// The bag being not empty, the opponent holds as many tiles as the
+    /// player.
+    ///
+    /// # Arguments
+    /// * `board` - The tiles already played.
+    /// * `rack` - The tiles of the player to move.
+    /// * `tileset` - The tiles of the game, telling the unseen ones.
+    /// * `dico` - The words allowed.
+    /// * `spread` - By how many points the player is ahead.
+    /// * `candidates` - How many of the best scoring moves are judged.
+    ///
+    /// # Return Value
+    /// None if the bag is empty or holds more tiles than a rack.
+    pub fn new(board : &Board, rack : &[Tile], tileset : &TileSet, dico : &Dico, spread : i32,
+            candidates : usize) -> Option<PreEndgame> {
+        let mut seen = rack.to_vec();
+        for y in 0..15 {
+            for x in 0..15 {
+                seen.extend(board.get_tile(x, y));
+            }
+        }
+        let unseen = tileset.unseen(&seen);
+        let bag = unseen.len().checked_sub(rack.len()).filter(|bag| (1..=rack.len()).contains(bag))?;
+        let splits = splits(&unseen, rack.len());
+        let total = splits.iter().map(|split| split.weight).sum::<usize>() as f64;
+
+        let mut moves : Vec<MoveOutcome> = Vec::new();
+        for candidate in best(board, rack, dico, candidates) {
+            let mut played = board.clone();
+            played.add_move(candidate.mv().clone(), candidate.tiles().clone());
+            let kept = left(rack, candidate.tiles());
+            let mut wins = 0.0;
+            let mut spreads = 0.0;
+            for split in splits.iter() {
+                let mut after = spread + candidate.score() as i32;
+                if candidate.tiles().len() >= bag {
+                    // The player draws the whole bag, everything is known
+                    let mut ours = kept.clone();
+                    ours.extend(split.bag.iter().cloned());
+                    after += play_out(&mut played.clone(), [ours, split.rack.clone()], 1, dico);
+                }
+                else {
+                    after -= best(&played, &split.rack, dico, 1).first().map_or(0, |reply| reply.score() as i32);
+                }
+                let weight = split.weight as f64;
+                if after > 0 {
+                    wins += weight;
+                }
+                else if after == 0 {
+                    wins += weight / 2.0;
+                }
+                spreads += after as f64 * weight;
+            }
+            moves.push(MoveOutcome {
+                mv : candidate.mv().clone(),
+                score : candidate.score(),
+                win_probability : wins / total,
+                average_spread : spreads / total,
+            });
+        }
+        moves.sort_by(|a, b| b.win_probability.total_cmp(&a.win_probability)
+            .then_with(|| b.average_spread.total_cmp(&a.average_spread)));
+        Some(PreEndgame { bag, racks : splits.len(), moves })
+    }
+
+    /// Write a move a line, with its score, win probability in percent and
+    /// average spread
+    pub fn to_text(&self, alphabet : &Alphabet) -> String {
+        let mut text = format!("{} in the bag, the opponent can hold {} racks\n", self.bag, self.racks);
+        for (rank, outcome) in self.moves.iter().enumerate() {
+            text.push_str(&format!("{}. {} : {} points, {:.1}% to win, spread {:+.1}\n", rank + 1,
+                outcome.mv.notation(alphabet), outcome.score, outcome.win_probability * 100.0,
+                outcome.average_spread));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Board, Dico, Move, Direction, Tile, TileSet, config::TileInfo};
+    use super::PreEndgame;
+
+    #[test]
+    fn pre_endgame() {
+        let tileset = TileSet::from_vec(vec![TileInfo::new("I", 3, 1), TileInfo::new("T", 3, 1),
+            TileInfo::new("S", 1, 10)]);
+        let ab = tileset.alphabet();
+        let dico = Dico::from_file("dico.txt", ab).unwrap();
+        let tile = |label : &str| {
+            let letter = ab.letter(label).unwrap();
+            Tile::new(letter, tileset.get_points(letter), false)
+        };
+        let mut board = Board::new();
+        board.add_move(Move::new(7, 7, ab.encode("IT").unwrap(), Direction::Horizontal),
+            vec![tile("I"), tile("T")]);
+        // Without a rack, the bag looks bigger than a rack
+        assert!(PreEndgame::new(&board, &[], &tileset, &dico, 0, 5).is_none());
+
+        let rack = [tile("I"), tile("T")];
+        let analysis = PreEndgame::new(&board, &rack, &tileset, &dico, 0, 5).unwrap();
+        // The opponent holds two of I, T and S, the last one is in the bag
+        assert_eq!((analysis.bag, analysis.racks), (1, 3));
+        assert_eq!(analysis.moves.len(), 4);
+        assert!(analysis.to_text(ab).starts_with("1 in the bag, the opponent can hold 3 racks\n1. "));
+        for pair in analysis.moves.windows(2) {
+            assert!(pair[0].win_probability >= pair[1].win_probability);
+        }
+        for outcome in analysis.moves.iter() {
+            assert!((0.0..=1.0).contains(&outcome.win_probability));
+        }
+        // Far enough ahead or behind, nothing the opponent holds matters
+        let ahead = PreEndgame::new(&board, &rack, &tileset, &dico, 100, 5).unwrap();
+        assert!(ahead.moves.iter().all(|outcome| outcome.win_probability == 1.0));
+        let behind = PreEndgame::new(&board, &rack, &tileset, &dico, -100, 5).unwrap();
+        assert!(behind.moves.iter().all(|outcome| outcome.win_probability == 0.0));
+    }
+}
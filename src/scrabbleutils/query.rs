@@ -0,0 +1,357 @@
+use super::{Dico, Alphabet, Letter};
+use super::config::WILDCARD;
+use std::fmt;
+use std::rc::Rc;
+
+/// Stands for any letter in a pattern
+const ANY_LETTER : char = '?';
+
+/// Tells which words a `Words` iterator gives
+trait Filter : Clone {
+    /// Get the filter for what follows, None if `letter` can't come next
+    fn step(&self, letter : Letter) -> Option<Self>;
+    /// Whether a word can end here
+    fn accepts(&self) -> bool;
+}
+
+/// Letters still available when looking for anagrams
+#[derive(Clone)]
+struct Rack {
+    /// The letters and how many of each are left
+    letters : Vec<(Letter, u8)>,
+    /// How many wildcards are left
+    wildcards : u8,
+    /// Whether every letter must be used
+    all : bool,
+}
+
+impl Rack {
+    /// Read a rack where `*` stands for a wildcard
+    ///
+    /// # Return Value
+    /// None if the rack has a letter that is not in `alphabet`
+    fn new(rack : &str, all : bool, alphabet : &Alphabet) -> Option<Rack> {
+        let mut letters : Vec<(Letter, u8)> = Vec::new();
+        let wildcards = rack.chars().filter(|c| *c == WILDCARD).count() as u8;
+        let rack : String = rack.chars().filter(|c| *c != WILDCARD).collect();
+        for c in alphabet.encode(&rack)? {
+            match letters.iter_mut().find(|e| e.0 == c) {
+                Some(e) => e.1 += 1,
+                None => letters.push((c, 1)),
+            }
+        }
+        Some(Rack { letters, wildcards, all })
+    }
+}
+
+impl Filter for Rack {
+    fn step(&self, letter : Letter) -> Option<Rack> {
+        let mut next = self.clone();
+        match next.letters.iter_mut().find(|e| e.0 == letter && e.1 > 0) {
+            Some(e) => e.1 -= 1,
+            None if next.wildcards > 0 => next.wildcards -= 1,
+            None => return None,
+        }
+        Some(next)
+    }
+
+    fn accepts(&self) -> bool {
+        !self.all || (self.wildcards == 0 && self.letters.iter().all(|e| e.1 == 0))
+    }
+}
+
+/// Letters a word must contain
+#[derive(Clone)]
+struct Containing {
+    /// The letters not met yet
+    missing : Vec<Letter>,
+}
+
+impl Filter for Containing {
+    fn step(&self, letter : Letter) -> Option<Containing> {
+        let mut next = self.clone();
+        if let Some(index) = next.missing.iter().position(|c| *c == letter) {
+            next.missing.swap_remove(index);
+        }
+        Some(next)
+    }
+
+    fn accepts(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// What one letter of a pattern can be
+#[derive(Clone, Debug, PartialEq)]
+enum Slot {
+    Any,
+    Letter(Letter),
+    OneOf(Vec<Letter>),
+}
+
+impl Slot {
+    fn matches(&self, letter : Letter) -> bool {
+        match self {
+            Slot::Any => true,
+            Slot::Letter(c) => *c == letter,
+            Slot::OneOf(letters) => letters.contains(&letter),
+        }
+    }
+}
+
+/// A pattern being matched
+#[derive(Clone)]
+struct Pattern {
+    slots : Rc<Vec<Slot>>,
+    /// The next slot to match
+    pos : usize,
+}
+
+impl Filter for Pattern {
+    fn step(&self, letter : Letter) -> Option<Pattern> {
+        match self.slots.get(self.pos) {
+            Some(slot) if slot.matches(letter) => Some(Pattern {
+                slots : Rc::clone(&self.slots),
+                pos : self.pos + 1,
+            }),
+            _ => None,
+        }
+    }
+
+    fn accepts(&self) -> bool {
+        self.pos == self.slots.len()
+    }
+}
+
+/// Why a pattern could not be understood
+#[derive(Debug, PartialEq)]
+pub enum PatternError {
+    /// A `[` is never closed, it is at this position
+    Unclosed(usize),
+    /// The `[]` at this position contains no letter
+    EmptySet(usize),
+    /// What is at this position is not a letter of the alphabet
+    UnknownLetter(usize),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::Unclosed(pos) => write!(f, "the [ at position {} is never closed", pos + 1),
+            PatternError::EmptySet(pos) => write!(f, "the [] at position {} contains no letter", pos + 1),
+            PatternError::UnknownLetter(pos) => write!(f, "unknown letter at position {}", pos + 1),
+        }
+    }
+}
+
+/// Read a pattern like `?A?E` or `C[AEIOU]T`
+///
+/// Letters written with several characters can be used, like `[CH]` for the
+/// `CH` letter alone.
+fn parse_pattern(pattern : &str, alphabet : &Alphabet) -> Result<Vec<Slot>, PatternError> {
+    let chars : Vec<char> = pattern.chars().collect();
+    let encode = |start : usize, end : usize| {
+        let run : String = chars[start..end].iter().collect();
+        alphabet.encode(&run).ok_or(PatternError::UnknownLetter(start))
+    };
+    let mut slots : Vec<Slot> = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        match chars[pos] {
+            ANY_LETTER => {
+                slots.push(Slot::Any);
+                pos += 1;
+            }
+            '[' => {
+                let end = chars[pos..].iter().position(|c| *c == ']')
+                    .ok_or(PatternError::Unclosed(pos))? + pos;
+                let letters = encode(pos + 1, end)?;
+                if letters.is_empty() {
+                    return Err(PatternError::EmptySet(pos));
+                }
+                slots.push(Slot::OneOf(letters));
+                pos = end + 1;
+            }
+            _ => {
+                let end = chars[pos..].iter().position(|c| *c == ANY_LETTER || *c == '[')
+                    .map_or(chars.len(), |end| end + pos);
+                slots.extend(encode(pos, end)?.into_iter().map(Slot::Letter));
+                pos = end;
+            }
+        }
+    }
+    Ok(slots)
+}
+
+/// A step of the walk through the dictionnary
+struct Frame<F> {
+    /// The filter for the letters after this node
+    filter : F,
+    /// The edges of the node still to visit
+    edges : std::ops::Range<usize>,
+}
+
+/// Iterate over the words of a `Dico` a filter accepts
+///
+/// The words come in the order of the letters in the alphabet of the
+/// dictionnary, which is not the alphabetical order of the strings for
+/// letters like `CH` or `Ñ`. The words are found while iterating, nothing is
+/// computed in advance.
+struct Words<'a, F : Filter> {
+    dico : &'a Dico,
+    stack : Vec<Frame<F>>,
+    /// The letters leading to the top of the stack
+    word : Vec<Letter>,
+}
+
+impl<'a, F : Filter> Words<'a, F> {
+    /// Create the iterator, giving nothing if there is no filter
+    fn new(dico : &'a Dico, filter : Option<F>) -> Words<'a, F> {
+        let stack = match filter {
+            None => Vec::new(),
+            Some(filter) => vec![Frame { filter, edges : dico.edges(dico.root()) }],
+        };
+        Words {
+            dico,
+            stack,
+            word : Vec::new(),
+        }
+    }
+}
+
+impl<'a, F : Filter> Iterator for Words<'a, F> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let frame = self.stack.last_mut()?;
+            let edge = match frame.edges.next() {
+                Some(edge) => edge,
+                None => {
+                    self.stack.pop();
+                    self.word.pop();
+                    continue;
+                }
+            };
+            let (letter, node) = self.dico.edge(edge);
+            if let Some(filter) = frame.filter.step(letter) {
+                let found = self.dico.is_terminal(node) && filter.accepts();
+                self.word.push(letter);
+                self.stack.push(Frame { filter, edges : self.dico.edges(node) });
+                if found {
+                    return Some(self.dico.alphabet().decode(&self.word));
+                }
+            }
+        }
+    }
+}
+
+impl Dico {
+    /// Get the words using all the letters of `rack`
+    ///
+    /// `*` in the rack stands for a wildcard.
+    pub fn anagrams<'a>(&'a self, rack : &str) -> impl Iterator<Item = String> + 'a {
+        Words::new(self, Rack::new(rack, true, self.alphabet()))
+    }
+
+    /// Get the words using some of the letters of `rack`
+    ///
+    /// `*` in the rack stands for a wildcard.
+    pub fn subanagrams<'a>(&'a self, rack : &str) -> impl Iterator<Item = String> + 'a {
+        Words::new(self, Rack::new(rack, false, self.alphabet()))
+    }
+
+    /// Get the words containing all the `letters`, in any order
+    pub fn containing<'a>(&'a self, letters : &str) -> impl Iterator<Item = String> + 'a {
+        let missing = self.alphabet().encode(letters);
+        Words::new(self, missing.map(|missing| Containing { missing }))
+    }
+
+    /// Get the words matching `pattern`
+    ///
+    /// In the pattern, `?` stands for any letter and `[AEIOU]` for one of the
+    /// letters between the brackets. Other characters stand for themselves.
+    ///
+    /// # Errors
+    /// If the pattern is malformed.
+    pub fn matching<'a>(&'a self, pattern : &str)
+            -> Result<impl Iterator<Item = String> + 'a, PatternError> {
+        let slots = Rc::new(parse_pattern(pattern, self.alphabet())?);
+        Ok(Words::new(self, Some(Pattern { slots, pos : 0 })))
+    }
+
+    /// Get the letters that can be put in front of `word` to make a word
+    pub fn front_hooks(&self, word : &str) -> Vec<String> {
+        let word = match self.alphabet().encode(word) {
+            Some(word) => word,
+            None => return Vec::new(),
+        };
+        self.children(self.root())
+            .filter(|(_, node)| self.walk(*node, &word)
+                .is_some_and(|end| self.is_terminal(end)))
+            .map(|(c, _)| self.alphabet().label(c).to_string())
+            .collect()
+    }
+
+    /// Get the letters that can be put after `word` to make a word
+    pub fn back_hooks(&self, word : &str) -> Vec<String> {
+        let node = self.alphabet().encode(word)
+            .and_then(|word| self.walk(self.root(), &word));
+        match node {
+            None => Vec::new(),
+            Some(node) => self.children(node)
+                .filter(|(_, next)| self.is_terminal(*next))
+                .map(|(c, _)| self.alphabet().label(c).to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Dico, Alphabet};
+    use super::PatternError;
+
+    fn dico() -> Dico {
+        let alphabet = Alphabet::new("ABCHOSTU".chars().map(|c| c.to_string())
+            .chain(std::iter::once("CH".to_string())).collect());
+        Dico::from_words(vec!["AT", "CAT", "CATS", "CUT", "COT", "ACT", "TACO",
+            "SCAT", "CAST", "TAB", "CHAT"], &alphabet)
+    }
+
+    #[test]
+    fn anagrams() {
+        let dico = dico();
+        assert_eq!(dico.anagrams("tca").collect::<Vec<String>>(), vec!["ACT", "CAT"]);
+        assert_eq!(dico.anagrams("*ct").collect::<Vec<String>>(),
+            vec!["ACT", "CAT", "COT", "CUT"]);
+        assert_eq!(dico.anagrams("TACH").collect::<Vec<String>>(), vec!["CHAT"]);
+        assert_eq!(dico.anagrams("ZAC").count(), 0);
+        assert_eq!(dico.subanagrams("CAT").collect::<Vec<String>>(),
+            vec!["ACT", "AT", "CAT"]);
+        assert_eq!(dico.containing("ST").collect::<Vec<String>>(),
+            vec!["CAST", "CATS", "SCAT"]);
+    }
+
+    #[test]
+    fn patterns() {
+        let dico = dico();
+        let matching = |pattern : &str| dico.matching(pattern)
+            .map(|words| words.collect::<Vec<String>>());
+        assert_eq!(matching("C?T"), Ok(vec!["CAT".to_string(), "COT".to_string(),
+            "CUT".to_string()]));
+        assert_eq!(matching("c[ao]t"), Ok(vec!["CAT".to_string(), "COT".to_string()]));
+        assert_eq!(matching("[CH]?T"), Ok(vec!["CHAT".to_string()]));
+        assert_eq!(matching("C[AO"), Err(PatternError::Unclosed(1)));
+        assert_eq!(matching("C[]"), Err(PatternError::EmptySet(1)));
+        assert_eq!(matching("?Z"), Err(PatternError::UnknownLetter(1)));
+    }
+
+    #[test]
+    fn hooks() {
+        let dico = dico();
+        assert_eq!(dico.front_hooks("AT"), vec!["C", "CH"]);
+        assert_eq!(dico.back_hooks("CAT"), vec!["S"]);
+        assert!(dico.back_hooks("XYZ").is_empty());
+    }
+}
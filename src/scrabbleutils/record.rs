@@ -0,0 +1,449 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader};
+use super::{Board, Move, Direction, Tile, TileSet, Alphabet, Letter, Hand};
+use super::event::{Event, Observer};
+use super::notation::parse_coordinates;
+
+/// Stands for a wildcard in the racks of a GCG file
+const GCG_BLANK : char = '?';
+/// Stands for a letter already on the board in the moves of a GCG file
+const GCG_PLAYED_THROUGH : char = '.';
+/// What the time penalties are written with in a GCG file
+const TIME_PENALTY : &str = "(time)";
+
+/// What a player did on a turn of a recorded game
+#[derive(Clone)]
+pub enum Play {
+    Placed(Move),
+    /// The number of tiles given back, which ones is not told
+    Exchanged(usize),
+    Passed,
+    /// The tiles left in a hand at the end of the game, their points taken
+    /// out from the player holding them or given to the player who went out
+    Leftover(Vec<Tile>),
+    /// Points added or taken out at the end of the game, like `(time)` for
+    /// going over time
+    Adjustment(String),
+}
+
+/// A turn of a recorded game
+#[derive(Clone)]
+pub struct RecordedTurn {
+    pub player : usize,
+    /// The hand before playing, empty if it is not known
+    pub rack : Vec<Tile>,
+    pub play : Play,
+    /// The points the turn made, negative for a penalty
+    pub score : i32,
+    /// The score of the player after the turn
+    pub total : i32,
+}
+
+/// A whole game, as written in GCG files
+///
+/// GCG is the format most scrabble programs save games in.
+#[derive(Clone, Default)]
+pub struct GameRecord {
+    /// The names of the players, in the order they play
+    pub players : Vec<String>,
+    pub turns : Vec<RecordedTurn>,
+}
+
+/// Why a GCG file could not be read
+#[derive(Debug)]
+pub enum RecordError {
+    /// The file could not be read
+    Io(io::Error),
+    /// A line is malformed, starting at 1
+    Syntax {
+        line : usize,
+        message : String,
+    },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Io(e) => write!(f, "{}", e),
+            RecordError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl From<io::Error> for RecordError {
+    fn from(e : io::Error) -> RecordError {
+        RecordError::Io(e)
+    }
+}
+
+/// Write a name in one word, to use it as a nickname
+fn nickname(name : &str) -> String {
+    let name : Vec<&str> = name.split_whitespace().collect();
+    name.join("_")
+}
+
+/// Write a rack, `?` standing for a wildcard
+fn write_rack(rack : &[Tile], alphabet : &Alphabet) -> String {
+    rack.iter()
+        .map(|t| if t.wildcard() { GCG_BLANK.to_string() } else { alphabet.label(t.letter()).to_string() })
+        .collect()
+}
+
+/// Read a rack, `?` standing for a wildcard
+fn read_rack(rack : &str, tileset : &TileSet) -> Option<Vec<Tile>> {
+    let letters : String = rack.chars().filter(|c| *c != GCG_BLANK).collect();
+    let mut tiles : Vec<Tile> = tileset.alphabet().encode(&letters)?.into_iter()
+        .map(|letter| Tile::new(letter, tileset.get_points(letter), false))
+        .collect();
+    tiles.extend(rack.chars().filter(|c| *c == GCG_BLANK).map(|_| Tile::new(0, 0, true)));
+    Some(tiles)
+}
+
+/// Read a move like `8H HELLO`, the dots being letters already on `board`
+fn read_move(coordinates : &str, word : &str, board : &Board, alphabet : &Alphabet) -> Option<Move> {
+    let (x, y, direction) = parse_coordinates(coordinates)?;
+    let mut letters : Vec<Letter> = Vec::with_capacity(word.len());
+    for (i, part) in word.split(GCG_PLAYED_THROUGH).enumerate() {
+        if i > 0 {
+            let position = letters.len() as u8;
+            let letter = match direction {
+                Direction::Horizontal => board.get_letter(x + position, y),
+                Direction::Vertical => board.get_letter(x, y + position),
+            };
+            letters.push(letter?);
+        }
+        letters.extend(alphabet.encode(part)?);
+    }
+    let mv = Move::new(x, y, letters, direction);
+    if !board.can_place(&mv) {
+        return None;
+    }
+    Some(mv)
+}
+
+impl RecordedTurn {
+    /// Get the tiles a placed move takes from the hand
+    ///
+    /// They are taken from the rack the way the game does, so the wildcards
+    /// are found again. Without a rack, the tiles get the points of the
+    /// `tileset`.
+    pub fn tiles(&self, board : &Board, tileset : &TileSet) -> Vec<Tile> {
+        let mv = match &self.play {
+            Play::Placed(mv) => mv,
+            _ => return Vec::new(),
+        };
+        let letters = board.needed_letters(mv);
+        match Hand::from_tiles(self.rack.clone()).remove(&letters) {
+            Some(tiles) => tiles,
+            None => letters.into_iter().map(|l| Tile::new(l, tileset.get_points(l), false)).collect(),
+        }
+    }
+}
+
+impl GameRecord {
+    /// Get the score of `player` after the last turn recorded
+    fn total(&self, player : usize) -> i32 {
+        self.turns.iter().rev().find(|t| t.player == player).map_or(0, |t| t.total)
+    }
+
+    /// Add the moves of the first `turns` turns to `board`
+    ///
+    /// # Panic
+    /// If a move does not fit on the board, which can't happen with a record
+    /// that was read or made by the game.
+    pub fn replay(&self, board : &mut Board, turns : usize, tileset : &TileSet) {
+        for turn in self.turns.iter().take(turns) {
+            if let Play::Placed(mv) = &turn.play {
+                let tiles = turn.tiles(board, tileset);
+                board.add_move(mv.clone(), tiles);
+            }
+        }
+    }
+
+    /// Write the game in the GCG format
+    pub fn to_gcg(&self, alphabet : &Alphabet) -> String {
+        let mut gcg = String::from("#character-encoding UTF-8\n");
+        for (i, name) in self.players.iter().enumerate() {
+            gcg.push_str(&format!("#player{} {} {}\n", i + 1, nickname(name), name));
+        }
+        for turn in self.turns.iter() {
+            let play = match &turn.play {
+                Play::Placed(mv) => mv.notation(alphabet),
+                Play::Exchanged(count) => format!("-{}", count),
+                Play::Passed => "-".to_string(),
+                Play::Leftover(tiles) => format!("({})", write_rack(tiles, alphabet)),
+                Play::Adjustment(reason) => reason.clone(),
+            };
+            let rack = write_rack(&turn.rack, alphabet);
+            let rack = if rack.is_empty() { String::new() } else { format!("{} ", rack) };
+            gcg.push_str(&format!(">{}: {}{} {:+} {}\n", nickname(&self.players[turn.player]), rack,
+                play, turn.score, turn.total));
+        }
+        gcg
+    }
+
+    /// Save the game in a GCG file
+    ///
+    /// # Errors
+    /// If the file can't be written.
+    pub fn save(&self, filename : &str, alphabet : &Alphabet) -> io::Result<()> {
+        std::fs::write(filename, self.to_gcg(alphabet))
+    }
+
+    /// Read a game from a GCG file
+    ///
+    /// See `from_gcg()`.
+    ///
+    /// # Errors
+    /// If the file can't be read or is malformed.
+    pub fn from_gcg_file(filename : &str, tileset : &TileSet) -> Result<GameRecord, RecordError> {
+        let file = File::open(filename)?;
+        GameRecord::from_gcg(BufReader::new(file), tileset)
+    }
+
+    /// Read a game in the GCG format
+    ///
+    /// The players are given by `#player<n> <nickname> <name>` lines, then each
+    /// turn is `><nickname>: <rack> <move> <score> <total>`. A move is either
+    /// placed like `8H HELLO`, dots standing for letters on the board, an
+    /// exchange like `-ABC` or `-3`, a pass `-`, or an adjustment at the end
+    /// of the game between parenthesis: the tiles left in uppercase like
+    /// `(AB?)`, or a reason like `(time)`. Other lines are ignored.
+    ///
+    /// # Errors
+    /// If a turn is malformed or its move can't be played, with its line.
+    pub fn from_gcg<R : BufRead>(reader : R, tileset : &TileSet) -> Result<GameRecord, RecordError> {
+        let alphabet = tileset.alphabet();
+        let mut record = GameRecord::default();
+        let mut nicknames : Vec<String> = Vec::new();
+        // Moves with dots need the letters already placed
+        let mut board = Board::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let error = |message : String| RecordError::Syntax { line : index + 1, message };
+            if let Some(player) = line.strip_prefix("#player") {
+                let mut parts = player.splitn(3, char::is_whitespace);
+                let (_, nick) = (parts.next(), parts.next().unwrap_or(""));
+                if nick.is_empty() {
+                    return Err(error("a player needs a nickname".to_string()));
+                }
+                let name = parts.next().map(|n| n.trim()).filter(|n| !n.is_empty()).unwrap_or(nick);
+                nicknames.push(nick.to_string());
+                record.players.push(name.to_string());
+                continue;
+            }
+            let turn = match line.strip_prefix('>') {
+                Some(turn) => turn,
+                None => continue,
+            };
+            let (nick, rest) = turn.split_once(':').ok_or_else(||
+                error("a turn starts with `><nickname>:`".to_string()))?;
+            let player = nicknames.iter().position(|n| n == nick.trim()).ok_or_else(||
+                error(format!("{} is not a player", nick.trim())))?;
+            let tokens : Vec<&str> = rest.split_whitespace().collect();
+            if tokens.len() < 3 {
+                return Err(error("a turn has a move, a score and a total".to_string()));
+            }
+            let (score, total) = (tokens[tokens.len() - 2], tokens[tokens.len() - 1]);
+            let score : i32 = score.trim_start_matches('+').parse().map_err(|_|
+                error(format!("`{}` is not a score", score)))?;
+            let total : i32 = total.parse().map_err(|_| error(format!("`{}` is not a score", total)))?;
+            let tokens = &tokens[..tokens.len() - 2];
+            // The rack comes first, unless the move is an adjustment without it
+            let (rack, play) = match tokens {
+                [rack, play @ ..] if !rack.starts_with('(') && !play.is_empty()
+                    && parse_coordinates(rack).is_none() => (*rack, play),
+                play => ("", play),
+            };
+            let rack = read_rack(rack, tileset).ok_or_else(||
+                error(format!("`{}` has letters that are not in the game", rack)))?;
+            let play = match play {
+                [reason] if reason.starts_with('(') => {
+                    let inner = reason.trim_start_matches('(').trim_end_matches(')');
+                    let tiles = Some(inner)
+                        .filter(|inner| inner.chars().all(|c| c == GCG_BLANK || !c.is_lowercase()))
+                        .and_then(|inner| read_rack(inner, tileset));
+                    match tiles {
+                        Some(tiles) => Play::Leftover(tiles),
+                        None => Play::Adjustment(reason.to_string()),
+                    }
+                }
+                ["-"] => Play::Passed,
+                [exchange] if exchange.starts_with('-') => {
+                    let exchange = &exchange[1..];
+                    match exchange.parse::<usize>() {
+                        Ok(count) => Play::Exchanged(count),
+                        Err(_) => Play::Exchanged(read_rack(exchange, tileset).ok_or_else(||
+                            error(format!("`{}` has letters that are not in the game", exchange)))?.len()),
+                    }
+                }
+                [coordinates, word] => {
+                    let mv = read_move(coordinates, word, &board, alphabet).ok_or_else(||
+                        error(format!("`{} {}` can't be played", coordinates, word)))?;
+                    Play::Placed(mv)
+                }
+                _ => return Err(error("expected a move like `8H HELLO`, `-ABC` or `-`".to_string())),
+            };
+            let turn = RecordedTurn { player, rack, play, score, total };
+            if let Play::Placed(mv) = &turn.play {
+                let tiles = turn.tiles(&board, tileset);
+                board.add_move(mv.clone(), tiles);
+            }
+            record.turns.push(turn);
+        }
+        Ok(record)
+    }
+}
+
+/// Keeps the record of a game while it is played
+pub struct GameRecorder {
+    record : RefCell<GameRecord>,
+    /// The hand of the player whose turn it is
+    rack : RefCell<Vec<Tile>>,
+}
+
+impl GameRecorder {
+    pub fn new() -> GameRecorder {
+        GameRecorder {
+            record : RefCell::new(GameRecord::default()),
+            rack : RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Get the record of the game so far
+    pub fn record(&self) -> GameRecord {
+        self.record.borrow().clone()
+    }
+
+    fn push(&self, player : usize, play : Play, score : i32, total : i32) {
+        let rack = self.rack.borrow().clone();
+        self.record.borrow_mut().turns.push(RecordedTurn { player, rack, play, score, total });
+    }
+
+    /// Record a turn adding `score` to the score of `player`
+    fn push_turn(&self, player : usize, play : Play, score : i32) {
+        let total = self.record.borrow().total(player) + score;
+        self.push(player, play, score, total);
+    }
+}
+
+impl Default for GameRecorder {
+    fn default() -> GameRecorder {
+        GameRecorder::new()
+    }
+}
+
+impl Observer for GameRecorder {
+    fn notify(&self, event : &Event) {
+        match event {
+            Event::GameStart { players } => self.record.borrow_mut().players = players.clone(),
+            Event::TurnStart { rack, .. } => *self.rack.borrow_mut() = rack.clone(),
+            Event::Placed { player, mv, breakdown } =>
+                self.push_turn(*player, Play::Placed(mv.clone()), breakdown.total() as i32),
+            Event::Exchanged { player, count } => self.push_turn(*player, Play::Exchanged(*count), 0),
+            Event::Passed { player } => self.push_turn(*player, Play::Passed, 0),
+            Event::GameEnd { standings } => {
+                let mut standings = standings.clone();
+                standings.sort_by_key(|s| s.player);
+                // The one who went out gets the tiles of the others, the
+                // others give their own
+                for standing in standings.iter().filter(|s| s.tiles != 0) {
+                    let (rack, tiles) = if standing.tiles > 0 {
+                        let others = standings.iter().filter(|s| s.player != standing.player);
+                        (Vec::new(), others.flat_map(|s| s.rack.iter().cloned()).collect())
+                    }
+                    else {
+                        (standing.rack.clone(), standing.rack.clone())
+                    };
+                    *self.rack.borrow_mut() = rack;
+                    // The scores don't go below 0
+                    let total = (self.record.borrow().total(standing.player) + standing.tiles).max(0);
+                    self.push(standing.player, Play::Leftover(tiles), standing.tiles, total);
+                }
+                self.rack.borrow_mut().clear();
+                for standing in standings.iter().filter(|s| s.penalty > 0) {
+                    self.push(standing.player, Play::Adjustment(TIME_PENALTY.to_string()),
+                        -(standing.penalty as i32), standing.score as i32);
+                }
+            }
+            Event::Challenged { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Board, TileSet, Event, Observer, Move, Direction, Standing, Tile, ScoreBreakdown};
+    use super::{GameRecord, GameRecorder, Play};
+
+    #[test]
+    fn gcg() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let gcg = "#character-encoding UTF-8\n\
+            #player1 Ann Ann Lee\n\
+            #player2 bob bob\n\
+            #note anything\n\
+            >Ann: AEHLLO? 8H HELLO +16 16\n\
+            >bob: ABCDEFG -3 +0 0\n\
+            >Ann: ABCDEF? J7 A.E +5 21\n\
+            >bob: ABCDEFG - +0 0\n\
+            >Ann: (time) -10 11\n";
+        let record = GameRecord::from_gcg(gcg.as_bytes(), &tileset).unwrap();
+        assert_eq!(record.players, vec!["Ann Lee", "bob"]);
+        assert_eq!(record.turns.len(), 5);
+        assert!(record.turns[0].rack[6].wildcard());
+        assert!(matches!(record.turns[1].play, Play::Exchanged(3)));
+        match &record.turns[2].play {
+            Play::Placed(mv) => assert_eq!(mv.word(), &ab.encode("ALE").unwrap()[..]),
+            _ => panic!("the dots should be read from the board"),
+        }
+        assert!(matches!(record.turns[4].play, Play::Adjustment(_)));
+        let mut board = Board::new();
+        record.replay(&mut board, 1, &tileset);
+        assert_eq!(board.get_letter(11, 7), ab.letter("O"));
+        assert_eq!(board.get_letter(8, 6), None);
+        // The dots are written as letters
+        assert_eq!(record.to_gcg(ab).replace("J7 ALE", "J7 A.E"), gcg.replace("#note anything\n", "")
+            .replace("#player1 Ann Ann Lee", "#player1 Ann_Lee Ann Lee")
+            .replace(">Ann:", ">Ann_Lee:"));
+
+        let error = |gcg : &str| GameRecord::from_gcg(gcg.as_bytes(), &tileset).err().unwrap().to_string();
+        assert_eq!(error("#player1 a a\n>b: A 8H A +1 1\n"), "line 2: b is not a player");
+        assert_eq!(error("#player1 a a\n>a: A 8P A +1 1\n"), "line 2: `8P A` can't be played");
+        assert_eq!(error("#player1 a a\n>a: A 8H A +x 1\n"), "line 2: `+x` is not a score");
+    }
+
+    #[test]
+    fn recorder() {
+        let tileset = TileSet::from_file("english_tileset.txt").unwrap();
+        let ab = tileset.alphabet();
+        let recorder = GameRecorder::new();
+        let rack : Vec<Tile> = ab.encode("AAT").unwrap().into_iter().map(|l| Tile::new(l, 1, false)).collect();
+        let at = Move::new(7, 7, ab.encode("AT").unwrap(), Direction::Horizontal);
+        let events = [
+            Event::GameStart { players : vec!["ann".to_string(), "bob".to_string()] },
+            Event::TurnStart { player : 0, round : 1, rack : rack.clone() },
+            Event::Placed { player : 0, mv : at, breakdown : ScoreBreakdown {
+                words : vec![(ab.encode("AT").unwrap(), 4)], bingo : 0 } },
+            Event::TurnStart { player : 1, round : 1, rack : Vec::new() },
+            Event::Passed { player : 1 },
+            Event::GameEnd { standings : vec![
+                Standing { player : 0, name : "ann".to_string(), score : 0, rack : Vec::new(), tiles : 2,
+                    penalty : 10 },
+                Standing { player : 1, name : "bob".to_string(), score : 0, rack : rack[..2].to_vec(),
+                    tiles : -2, penalty : 0 }] },
+        ];
+        for event in events.iter() {
+            recorder.notify(event);
+        }
+        assert_eq!(recorder.record().to_gcg(ab), "#character-encoding UTF-8\n#player1 ann ann\n\
+            #player2 bob bob\n>ann: AAT 8H AT +4 4\n>bob: - +0 0\n>ann: (AA) +2 6\n\
+            >bob: AA (AA) -2 0\n>ann: (time) -10 0\n");
+        let record = GameRecord::from_gcg(recorder.record().to_gcg(ab).as_bytes(), &tileset).unwrap();
+        assert!(matches!(&record.turns[3].play, Play::Leftover(tiles) if tiles.len() == 2));
+        assert!(matches!(record.turns[4].play, Play::Adjustment(_)));
+    }
+}
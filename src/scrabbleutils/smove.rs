@@ -1,3 +1,5 @@
+use super::{Letter, Tile};
+
 /// Describe the orientation of a `Move`
 #[derive(Copy, Clone)]
 pub enum Direction {
@@ -5,28 +7,29 @@ pub enum Direction {
     Vertical,
 }
 
-/// Describe a player action in the game
+/// Describe a word placement in the game
+#[derive(Clone)]
 pub struct Move {
     /// Position of the first letter of the word on the absciss
     x : u8,
     /// Position of the first letter of the word on the ordinate
     y : u8,
     /// The word itself
-    word : String,
+    word : Vec<Letter>,
     /// The direction the word is being placed
     direction : Direction,
 }
 
 impl Move {
     /// Create a Move
-    pub fn new(x : u8, y : u8, word : String, direction : Direction) -> Move {
+    pub fn new(x : u8, y : u8, word : Vec<Letter>, direction : Direction) -> Move {
         Move {
             x, y, word, direction
         }
     }
 
     /// Get the word
-    pub fn word(&self) -> &str {
+    pub fn word(&self) -> &[Letter] {
         return &self.word;
     }
 
@@ -45,3 +48,13 @@ impl Move {
         return self.y;
     }
 }
+
+/// Describe what a player does on its turn
+pub enum Action {
+    /// Place a word on the board
+    Place(Move),
+    /// Give these tiles of the hand back to the bag for new ones
+    Exchange(Vec<Tile>),
+    /// Don't play anything this turn
+    Pass,
+}
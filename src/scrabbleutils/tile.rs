@@ -1,7 +1,9 @@
+use super::Letter;
+
 /// Describe a game piece
 #[derive(Clone, PartialEq, Debug)]
 pub struct Tile {
-    letter : char,
+    letter : Letter,
     wildcard : bool,
     points : u8,
 }
@@ -10,14 +12,15 @@ impl Tile {
     /// Create a tile
     ///
     /// # Arguments
-    /// * `letter` - The letter on the tile
+    /// * `letter` - The letter on the tile, meaningless for a joker until it
+    ///   is set with `set_wildcard()`
     /// * `points` - The amount of points this letter gives
     /// * `wildcard` - Whether this tile is a joker
-    pub fn new(letter : char, points : u8, wildcard : bool) -> Tile {
+    pub fn new(letter : Letter, points : u8, wildcard : bool) -> Tile {
         Tile { letter, wildcard, points}
     }
     /// Get the letter
-    pub fn letter(&self) -> char {
+    pub fn letter(&self) -> Letter {
         self.letter
     }
     /// Get the wildcard field
@@ -35,7 +38,7 @@ impl Tile {
     ///
     /// # Argument
     /// * `c` - The letter to set
-    pub fn set_wildcard(&mut self, c : char) {
+    pub fn set_wildcard(&mut self, c : Letter) {
         if self.wildcard == true {
             self.letter = c;
         }
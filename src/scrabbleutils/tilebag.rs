@@ -1,4 +1,6 @@
-use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use super::{Tile, TileSet};
 
 
@@ -7,6 +9,8 @@ use super::{Tile, TileSet};
 /// It's content is randomised
 pub struct TileBag {
     tiles : Vec<Tile>,
+    /// Shuffles the bag
+    rng : StdRng,
 }
 
 impl TileBag {
@@ -17,21 +21,33 @@ impl TileBag {
     /// # Argument
     /// `ts` - The `TileSet` to use
     pub fn new(ts : &TileSet) -> TileBag {
-        let mut rng = rand::thread_rng();
         let mut tiles : Vec<Tile> = Vec::with_capacity(103);
-        // Avoid calling gen_rand(0, 0)
-        let dummy_tile = Tile::new('_', 0, true);
-        tiles.push(dummy_tile.clone());
-
         for line in ts.infos() {
             let count = line.occurences();
             for _ in 0..count {
-                tiles.insert(rng.gen_range(0, tiles.len()),
-                    line.tile());
+                tiles.push(line.tile());
             }
         }
-        tiles.swap_remove(tiles.iter().position(|e| e == &dummy_tile).unwrap());
-        TileBag { tiles }
+        let mut rng = StdRng::from_entropy();
+        tiles.shuffle(&mut rng);
+        TileBag { tiles, rng }
+    }
+
+    /// Create a TileBag holding exactly `tiles`, shuffled
+    pub fn from_tiles(tiles : Vec<Tile>) -> TileBag {
+        let mut tiles = tiles;
+        let mut rng = StdRng::from_entropy();
+        tiles.shuffle(&mut rng);
+        TileBag { tiles, rng }
+    }
+
+    /// Shuffle the bag again, the same way for the same `seed`
+    ///
+    /// Games with the same seed draw the same tiles.
+    pub fn reseed(&mut self, seed : u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.tiles.sort_by_key(|t| (t.wildcard(), t.letter(), t.points()));
+        self.tiles.shuffle(&mut self.rng);
     }
 
     /// Whether the bag is empty
@@ -39,6 +55,17 @@ impl TileBag {
         return self.tiles.len() == 0;
     }
 
+    /// Get the number of tiles left in the bag
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Put tiles back in the bag and shuffle it
+    pub fn put_back(&mut self, tiles : Vec<Tile>) {
+        self.tiles.extend(tiles);
+        self.tiles.shuffle(&mut self.rng);
+    }
+
     /// Take a `Tile` from the bag
     /// Returns None if the bag is empty
     pub fn pick(&mut self) -> Option<Tile> {